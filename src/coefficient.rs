@@ -0,0 +1,5 @@
+// the trait shares its module's name, like `expr::expr`
+#[allow(clippy::module_inception)]
+pub mod coefficient;
+pub mod rational;
+pub mod modular;
\ No newline at end of file
@@ -0,0 +1,116 @@
+use std::fmt::{Debug, Display};
+use std::ops::{Add, Mul, Neg, Sub};
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::expr::print::PrintStyle;
+
+// Coefficients of `Mono` and `Poly`.
+//
+// Polynomial arithmetic needs zero, one, the ring operations, a zero test
+// and inverses where they exist. Division of monomials goes through
+// `divide`, so a domain that is not a field (the integers, say) still works
+// as long as it reports which quotients it can take. `Number` keeps the
+// floating point behaviour the crate always had, `f64` is the bare float
+// and `Rational` is exact. `to_number` is the approximate value used for
+// the `Expr` view of a monomial, which is also what printing falls back to.
+//
+// Any other type implementing the trait can be used in `Mono`, `Poly` and
+// `Ring` the same way.
+
+pub trait Coefficient: Clone + Debug + PartialEq + Display
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn is_zero(&self) -> bool;
+    // None when `self` is not a unit
+    fn inverse(&self) -> Option<Self>;
+    fn to_number(&self) -> Number;
+    // short name of the domain, e.g. `QQ`
+    fn domain() -> String;
+
+    // the c with `other * c == self`, if there is one
+    fn divide(&self, other: &Self) -> Option<Self> {
+        return other.inverse().map(|inverse| self.clone() * inverse);
+    }
+
+    fn is_negative(&self) -> bool {
+        return self.to_number() < 0.0;
+    }
+
+    // `self` times the monomial with exponents `variables`, as printed in a polynomial
+    fn term(&self, variables: &Variables, style: PrintStyle) -> String {
+        return Term::from((self.to_number(), variables.clone())).pretty(style);
+    }
+
+    // the coefficient on its own, as written in LaTeX
+    fn latex(&self) -> String {
+        return self.to_number().to_string();
+    }
+}
+
+impl Coefficient for Number {
+    fn zero() -> Self {
+        return Number::Decimal(0.0);
+    }
+
+    fn one() -> Self {
+        return Number::Decimal(1.0);
+    }
+
+    fn is_zero(&self) -> bool {
+        return *self == 0.0;
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        return Some(Number::Decimal(1.0) / self.clone());
+    }
+
+    fn to_number(&self) -> Number {
+        return self.clone();
+    }
+
+    fn domain() -> String {
+        return String::from("RR");
+    }
+
+    // keeps fractions exact where `Number` can
+    fn divide(&self, other: &Self) -> Option<Self> {
+        if other.is_zero() {
+            return None;
+        }
+        return Some(self.clone() / other.clone());
+    }
+}
+
+impl Coefficient for f64 {
+    fn zero() -> Self {
+        return 0.0;
+    }
+
+    fn one() -> Self {
+        return 1.0;
+    }
+
+    fn is_zero(&self) -> bool {
+        return *self == 0.0;
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        return Some(1.0 / self);
+    }
+
+    fn to_number(&self) -> Number {
+        return Number::Decimal(*self);
+    }
+
+    fn domain() -> String {
+        return String::from("RDF");
+    }
+}
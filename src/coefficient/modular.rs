@@ -0,0 +1,165 @@
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+use num_notation::*;
+
+use crate::coefficient::coefficient::Coefficient;
+use crate::coefficient::rational::Rational;
+
+// The prime field GF(P) for a word-sized prime P, with elements stored as
+// their residue in 0..P. Products are taken in u128, so any prime below
+// 2^64 works; P is not checked for primality.
+//
+// `PRIMES` are the primes the multi-modular Gröbner basis runs over, the
+// sixteen largest below 2^62.
+
+pub const PRIMES: [u64; 16] = [
+    4611686018427387847, 4611686018427387817, 4611686018427387787, 4611686018427387761,
+    4611686018427387751, 4611686018427387737, 4611686018427387733, 4611686018427387709,
+    4611686018427387701, 4611686018427387631, 4611686018427387617, 4611686018427387587,
+    4611686018427387461, 4611686018427387421, 4611686018427387409, 4611686018427387329,
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GF<const P: u64>(pub(crate) u64);
+
+impl<const P: u64> GF<P> {
+    pub fn new(n: i64) -> GF<P> {
+        return GF((n as i128).rem_euclid(P as i128) as u64);
+    }
+
+    pub fn residue(&self) -> u64 {
+        return self.0;
+    }
+
+    pub fn pow(&self, mut exp: u64) -> GF<P> {
+        let mut base: GF<P> = *self;
+        let mut result: GF<P> = GF(1 % P);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = result * base;
+            }
+            base = base * base;
+            exp >>= 1;
+        }
+        return result;
+    }
+
+    // None when P divides the denominator
+    pub fn from_rational(q: &Rational) -> Option<GF<P>> {
+        let reduce = |n: &BigInt| -> GF<P> {
+            let r: BigInt = n % BigInt::from(P);
+            let r: BigInt = if r.is_negative() { r + BigInt::from(P) } else { r };
+            return GF(r.to_u64().unwrap());
+        };
+        let denominator: GF<P> = reduce(q.denominator());
+        return denominator.inverse().map(|inverse| reduce(q.numerator()) * inverse);
+    }
+}
+
+impl<const P: u64> Display for GF<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        return write!(f, "{}", self.0);
+    }
+}
+
+impl<const P: u64> Add for GF<P> {
+    type Output = GF<P>;
+    fn add(self, other: GF<P>) -> GF<P> {
+        return GF(((self.0 as u128 + other.0 as u128) % P as u128) as u64);
+    }
+}
+
+impl<const P: u64> Sub for GF<P> {
+    type Output = GF<P>;
+    fn sub(self, other: GF<P>) -> GF<P> {
+        return self + (-other);
+    }
+}
+
+impl<const P: u64> Mul for GF<P> {
+    type Output = GF<P>;
+    fn mul(self, other: GF<P>) -> GF<P> {
+        return GF(((self.0 as u128 * other.0 as u128) % P as u128) as u64);
+    }
+}
+
+impl<const P: u64> Div for GF<P> {
+    type Output = GF<P>;
+    // multiplication by the inverse
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, other: GF<P>) -> GF<P> {
+        return self * other.inverse().expect("division by zero in GF(p)");
+    }
+}
+
+impl<const P: u64> Neg for GF<P> {
+    type Output = GF<P>;
+    fn neg(self) -> GF<P> {
+        if self.0 == 0 {
+            return self;
+        }
+        return GF(P - self.0);
+    }
+}
+
+impl<const P: u64> Coefficient for GF<P> {
+    fn zero() -> Self {
+        return GF(0);
+    }
+
+    fn one() -> Self {
+        return GF(1 % P);
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.0 == 0;
+    }
+
+    // extended Euclid on (P, self)
+    fn inverse(&self) -> Option<Self> {
+        let (mut r0, mut r1): (i128, i128) = (P as i128, self.0 as i128);
+        let (mut t0, mut t1): (i128, i128) = (0, 1);
+        while r1 != 0 {
+            let q: i128 = r0 / r1;
+            (r0, r1) = (r1, r0 - q * r1);
+            (t0, t1) = (t1, t0 - q * t1);
+        }
+        if r0 != 1 {
+            return None;
+        }
+        return Some(GF(t0.rem_euclid(P as i128) as u64));
+    }
+
+    fn to_number(&self) -> Number {
+        return Number::Decimal(self.0 as f64);
+    }
+
+    fn domain() -> String {
+        return format!("GF({})", P);
+    }
+}
+
+// c with c = residue mod modulus and |numerator|, denominator <= sqrt(modulus/2)
+pub fn rational_reconstruction(residue: &BigInt, modulus: &BigInt) -> Option<Rational> {
+    let bound: BigInt = (modulus / BigInt::from(2)).sqrt();
+    let (mut r0, mut r1): (BigInt, BigInt) = (modulus.clone(), ((residue % modulus) + modulus) % modulus);
+    let (mut t0, mut t1): (BigInt, BigInt) = (BigInt::zero(), BigInt::from(1));
+    while r1 > bound {
+        let q: BigInt = &r0 / &r1;
+        let r2: BigInt = &r0 - &q * &r1;
+        let t2: BigInt = &t0 - &q * &t1;
+        (r0, r1) = (r1, r2);
+        (t0, t1) = (t1, t2);
+    }
+    if t1.is_zero() || t1.abs() > bound {
+        return None;
+    }
+    let q: Rational = Rational::new(r1.clone(), t1.clone());
+    // numerator and denominator have to be coprime
+    if q.denominator() != &t1.abs() {
+        return None;
+    }
+    return Some(q);
+}
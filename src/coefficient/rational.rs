@@ -0,0 +1,204 @@
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use num_notation::*;
+
+use crate::coefficient::coefficient::Coefficient;
+use crate::expr::expr::*;
+use crate::expr::print::PrintStyle;
+
+// Exact rational numbers over arbitrary precision integers, kept in lowest
+// terms with a positive denominator.
+//
+// A float is never turned into a rational implicitly: `from_f64` has to be
+// called, and it picks the first continued fraction convergent that rounds
+// back to the same float, so 0.1 becomes 1/10 rather than its binary value.
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rational(pub(crate) BigRational);
+
+impl Rational {
+    // panics on a zero denominator
+    pub fn new(numerator: impl Into<BigInt>, denominator: impl Into<BigInt>) -> Rational {
+        let denominator: BigInt = denominator.into();
+        assert!(!denominator.is_zero(), "rational with zero denominator");
+        return Rational(BigRational::new(numerator.into(), denominator));
+    }
+
+    pub fn integer(n: impl Into<BigInt>) -> Rational {
+        return Rational(BigRational::from_integer(n.into()));
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        return self.0.numer();
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        return self.0.denom();
+    }
+
+    pub fn is_integer(&self) -> bool {
+        return self.0.is_integer();
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        return self.0.to_f64().unwrap_or(f64::NAN);
+    }
+
+    // None for NaN and infinities
+    pub fn from_f64(x: f64) -> Option<Rational> {
+        let exact: BigRational = BigRational::from_float(x)?;
+        // convergents h/k of the continued fraction of the exact binary value
+        let (mut h0, mut h1): (BigInt, BigInt) = (BigInt::zero(), BigInt::one());
+        let (mut k0, mut k1): (BigInt, BigInt) = (BigInt::one(), BigInt::zero());
+        let mut rest: BigRational = exact.clone();
+        loop {
+            let a: BigInt = rest.floor().to_integer();
+            let h: BigInt = &a * &h1 + &h0;
+            let k: BigInt = &a * &k1 + &k0;
+            let convergent: BigRational = BigRational::new(h.clone(), k.clone());
+            if convergent.to_f64() == Some(x) {
+                return Some(Rational(convergent));
+            }
+            let fraction: BigRational = rest - BigRational::from_integer(a);
+            if fraction.is_zero() {
+                return Some(Rational(exact));
+            }
+            rest = fraction.recip();
+            (h0, h1) = (h1, h);
+            (k0, k1) = (k1, k);
+        }
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        return Rational::integer(n);
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(n: BigInt) -> Self {
+        return Rational::integer(n);
+    }
+}
+
+impl TryFrom<&Number> for Rational {
+    type Error = String;
+
+    fn try_from(n: &Number) -> Result<Self, Self::Error> {
+        let x: f64 = f64::from(n.clone());
+        return Rational::from_f64(x).ok_or(format!("{} is not a finite number", x));
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            return write!(f, "{}", self.numerator());
+        }
+        return write!(f, "{}/{}", self.numerator(), self.denominator());
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+    fn add(self, other: Rational) -> Rational {
+        return Rational(self.0 + other.0);
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+    fn sub(self, other: Rational) -> Rational {
+        return Rational(self.0 - other.0);
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+    fn mul(self, other: Rational) -> Rational {
+        return Rational(self.0 * other.0);
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+    fn div(self, other: Rational) -> Rational {
+        assert!(!other.is_zero(), "division of {} by zero", self);
+        return Rational(self.0 / other.0);
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+    fn neg(self) -> Rational {
+        return Rational(-self.0);
+    }
+}
+
+impl Coefficient for Rational {
+    fn zero() -> Self {
+        return Rational(BigRational::zero());
+    }
+
+    fn one() -> Self {
+        return Rational(BigRational::one());
+    }
+
+    fn is_zero(&self) -> bool {
+        return self.0.is_zero();
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        return Some(Rational(self.0.recip()));
+    }
+
+    fn to_number(&self) -> Number {
+        return Number::Decimal(self.to_f64());
+    }
+
+    fn domain() -> String {
+        return String::from("QQ");
+    }
+
+    fn is_negative(&self) -> bool {
+        return self.0.is_negative();
+    }
+
+    // exact coefficient in front of the unit monomial, e.g. `1/3*x^2`
+    fn term(&self, variables: &Variables, style: PrintStyle) -> String {
+        let unit: Variables = variables.iter()
+            .filter(|(_, n)| **n != 0.0)
+            .map(|(s, n)| (s.clone(), n.clone()))
+            .collect();
+        if unit.is_empty() {
+            return self.to_string();
+        }
+        let monomial: String = Term::from((Number::Decimal(1.0), unit)).pretty(style);
+        if self.0.is_one() {
+            return monomial;
+        }
+        if (-self.0.clone()).is_one() {
+            return format!("-{}", monomial);
+        }
+        let times: &str = match style {
+            PrintStyle::Unicode => "·",
+            _ => "*",
+        };
+        return format!("{}{}{}", self, times, monomial);
+    }
+
+    fn latex(&self) -> String {
+        if self.is_integer() {
+            return self.numerator().to_string();
+        }
+        let sign: &str = if self.is_negative() { "-" } else { "" };
+        return format!("{}\\frac{{{}}}{{{}}}", sign, self.numerator().magnitude(), self.denominator());
+    }
+}
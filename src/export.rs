@@ -0,0 +1,3 @@
+pub mod latex;
+pub mod mathml;
+pub mod fullform;
@@ -0,0 +1,338 @@
+use num_notation::*;
+use std::fmt::Display;
+
+use crate::expr::expr::*;
+use crate::expr::parse::ParseError;
+
+// Mathematica FullForm export and import, e.g. `Plus[Times[3, Power[x, 2]], Sin[t]]`.
+//
+// Every binary node keeps its own head (`Subtract`, `Divide`, `Minus` are valid
+// Mathematica input), so `from_full_form(e.to_full_form())` rebuilds the same
+// tree. The exceptions are SQUARE, written as `Power[a, 2]`, and PARAM leaves
+// holding a coefficient or several variables, written as a `Times` of powers;
+// those come back as equivalent POW and TIMES nodes.
+//
+// Mathematica reads `_` in a name as a pattern, so `theta_1` is written as
+// `theta\[LetterSpace]1` and read back as `theta_1`.
+
+const LETTER_SPACE: &str = "\\[LetterSpace]";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportError {
+    pub node: String, // the subexpression without a FullForm head, printed
+    pub message: String,
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`: {}", self.node, self.message)
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+fn symbol(name: &str) -> String {
+    return name.replace('_', LETTER_SPACE);
+}
+
+fn number(n: &Number) -> String {
+    return n.to_string();
+}
+
+fn term(x: &Term) -> String {
+    let mut factors: Vec<String> = Vec::new();
+    if x.coefficient != 1.0 || x.variables.is_empty() {
+        factors.push(number(&x.coefficient));
+    }
+    for (s, n) in x.variables.iter().filter(|(_, n)| **n != 0.0) {
+        if *n == 1.0 {
+            factors.push(symbol(s));
+        }
+        else {
+            factors.push(format!("Power[{}, {}]", symbol(s), number(n)));
+        }
+    }
+    if factors.len() == 1 {
+        return factors.remove(0);
+    }
+    return format!("Times[{}]", factors.join(", "));
+}
+
+fn head(op: &EnumExpr) -> Option<&'static str> {
+    match op {
+        EnumExpr::PLUS => Some("Plus"),
+        EnumExpr::MINUS => Some("Subtract"),
+        EnumExpr::TIMES => Some("Times"),
+        EnumExpr::DIV => Some("Divide"),
+        EnumExpr::POW => Some("Power"),
+        EnumExpr::NEGATE => Some("Minus"),
+        EnumExpr::SQRT => Some("Sqrt"),
+        EnumExpr::SIN => Some("Sin"),
+        EnumExpr::COS => Some("Cos"),
+        EnumExpr::ASIN => Some("ArcSin"),
+        EnumExpr::ACOS => Some("ArcCos"),
+        EnumExpr::LOG => Some("Log"),
+        _ => None,
+    }
+}
+
+fn write(e: &Expr) -> Result<String, ExportError> {
+    match e.op() {
+        EnumExpr::CONSTANT | EnumExpr::PARAM => Ok(term(&e.x)),
+        EnumExpr::SQUARE => Ok(format!("Power[{}, 2]", write(e.a.as_ref().unwrap())?)),
+        EnumExpr::INTEGRAL => Ok(format!("Integrate[{}, {}]", write(e.a.as_ref().unwrap())?, symbol(&e.integration_variable()))),
+        EnumExpr::DEFINITE => {
+            let (lower, upper) = e.bounds();
+            let integrand: &Expr = e.a.as_ref().unwrap().a.as_ref().unwrap();
+            Ok(format!("Integrate[{}, List[{}, {}, {}]]", write(integrand)?, symbol(&e.integration_variable()), write(lower)?, write(upper)?))
+        }
+        op => {
+            let head: &str = match head(&op) {
+                Some(head) => head,
+                None => return Err(ExportError { node: e.to_string(), message: format!("no FullForm head for {}", op) }),
+            };
+            if e.children() == 2 {
+                return Ok(format!("{}[{}, {}]", head, write(e.a.as_ref().unwrap())?, write(e.b.as_ref().unwrap())?));
+            }
+            return Ok(format!("{}[{}]", head, write(e.a.as_ref().unwrap())?));
+        }
+    }
+}
+
+// FullForm reader: `Head[arg, ...]`, symbols and numbers, including `*^`
+// exponents and `Rational[p, q]`.
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Reader {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        ParseError { column: self.pos + 1, message: message.to_string() }
+    }
+
+    fn number(&mut self) -> Result<Expr, ParseError> {
+        let start: usize = self.pos;
+        if self.chars[self.pos] == '-' {
+            self.pos += 1;
+        }
+        while self.pos < self.chars.len() && (self.chars[self.pos].is_ascii_digit() || self.chars[self.pos] == '.') {
+            self.pos += 1;
+        }
+        let mantissa: String = self.chars[start..self.pos].iter().collect();
+        let mut val: f64 = match mantissa.parse::<f64>() {
+            Ok(val) => val,
+            Err(_) => {
+                self.pos = start;
+                return Err(self.error(&format!("invalid number `{}`", mantissa)));
+            }
+        };
+        // Mathematica writes 1.5*10^-3 as 1.5*^-3
+        if self.chars[self.pos..].starts_with(&['*', '^']) {
+            self.pos += 2;
+            let exp_start: usize = self.pos;
+            if self.pos < self.chars.len() && self.chars[self.pos] == '-' {
+                self.pos += 1;
+            }
+            while self.pos < self.chars.len() && self.chars[self.pos].is_ascii_digit() {
+                self.pos += 1;
+            }
+            let exponent: String = self.chars[exp_start..self.pos].iter().collect();
+            match exponent.parse::<i32>() {
+                Ok(exp) => val *= 10f64.powi(exp),
+                Err(_) => {
+                    self.pos = exp_start;
+                    return Err(self.error("invalid exponent"));
+                }
+            }
+        }
+        return Ok(Expr::from(val));
+    }
+
+    fn arguments(&mut self) -> Result<Vec<Expr>, ParseError> {
+        let mut args: Vec<Expr> = Vec::new();
+        self.skip_whitespace();
+        if self.pos < self.chars.len() && self.chars[self.pos] == ']' {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.expr()?);
+            self.skip_whitespace();
+            match self.chars.get(self.pos) {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(args);
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    // letters, digits and `\[LetterSpace]`, which is read as `_`
+    fn name(&mut self) -> String {
+        let mut name: String = String::new();
+        while self.pos < self.chars.len() {
+            let c: char = self.chars[self.pos];
+            if c.is_alphanumeric() || c == '_' {
+                name.push(c);
+                self.pos += 1;
+            }
+            else if c == '\\' && self.chars[self.pos..].iter().take(LETTER_SPACE.len()).collect::<String>() == LETTER_SPACE {
+                name.push('_');
+                self.pos += LETTER_SPACE.len();
+            }
+            else {
+                break;
+            }
+        }
+        return name;
+    }
+
+    // a single symbol, e.g. the integration variable
+    fn symbol(&mut self) -> Result<String, ParseError> {
+        self.skip_whitespace();
+        let start: usize = self.pos;
+        let name: String = self.name();
+        if name.is_empty() || self.chars[start].is_ascii_digit() {
+            self.pos = start;
+            return Err(self.error("expected a symbol"));
+        }
+        return Ok(name);
+    }
+
+    fn consume(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.chars.get(self.pos) != Some(&c) {
+            return Err(self.error(&format!("expected `{}`", c)));
+        }
+        self.pos += 1;
+        return Ok(());
+    }
+
+    // Integrate[f, x] or Integrate[f, List[x, lower, upper]], after the `[`
+    fn integrate(&mut self) -> Result<Expr, ParseError> {
+        let integrand: Expr = self.expr()?;
+        self.consume(',')?;
+        let name: String = self.symbol()?;
+        self.skip_whitespace();
+        if name != "List" || self.chars.get(self.pos) != Some(&'[') {
+            self.consume(']')?;
+            return Ok(integrand.Integral(&name));
+        }
+        self.pos += 1;
+        let x: String = self.symbol()?;
+        self.consume(',')?;
+        let lower: Expr = self.expr()?;
+        self.consume(',')?;
+        let upper: Expr = self.expr()?;
+        self.consume(']')?;
+        self.consume(']')?;
+        return Ok(integrand.Definite(&x, lower, upper));
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        self.skip_whitespace();
+        let c: char = match self.chars.get(self.pos) {
+            Some(c) => *c,
+            None => return Err(self.error("unexpected end of input")),
+        };
+        if c.is_ascii_digit() || c == '-' || c == '.' {
+            return self.number();
+        }
+        let start: usize = self.pos;
+        let name: String = self.name();
+        if name.is_empty() {
+            return Err(self.error(&format!("unexpected character `{}`", c)));
+        }
+        self.skip_whitespace();
+        if self.chars.get(self.pos) != Some(&'[') {
+            return match name.as_str() {
+                "Pi" => Ok(Expr::from(std::f64::consts::PI)),
+                "E" => Ok(Expr::from(std::f64::consts::E)),
+                _ => Ok(Expr::from(name)),
+            };
+        }
+        self.pos += 1;
+        let column: usize = start + 1;
+        if name == "Integrate" {
+            return self.integrate();
+        }
+        let mut args: Vec<Expr> = self.arguments()?;
+        let arity = |n: usize| -> Result<(), ParseError> {
+            if args.len() == n {
+                return Ok(());
+            }
+            return Err(ParseError { column, message: format!("`{}` takes {} argument(s), found {}", name, n, args.len()) });
+        };
+        let result: Expr = match name.as_str() {
+            "Plus" | "Times" => {
+                if args.is_empty() {
+                    return Ok(Expr::from(if name == "Plus" { 0.0 } else { 1.0 }));
+                }
+                let mut acc: Expr = args.remove(0);
+                for arg in args.into_iter() {
+                    acc = if name == "Plus" { acc.Plus(arg) } else { acc.Times(arg) };
+                }
+                acc
+            }
+            "Subtract" | "Divide" | "Power" | "Rational" => {
+                arity(2)?;
+                let b: Expr = args.pop().unwrap();
+                let a: Expr = args.pop().unwrap();
+                match name.as_str() {
+                    "Subtract" => a.Minus(b),
+                    "Divide" => a.Div(b),
+                    "Power" => a.Pow(b),
+                    _ => {
+                        if a.op() != EnumExpr::CONSTANT || b.op() != EnumExpr::CONSTANT {
+                            return Err(ParseError { column, message: "`Rational` takes two numbers".to_string() });
+                        }
+                        if b.x.coefficient == 0.0 {
+                            return Err(ParseError { column, message: "`Rational` with a zero denominator".to_string() });
+                        }
+                        Expr::from(f64::from(a.x.coefficient) / f64::from(b.x.coefficient))
+                    }
+                }
+            }
+            "Minus" | "Sqrt" | "Sin" | "Cos" | "ArcSin" | "ArcCos" | "Log" => {
+                arity(1)?;
+                let a: Expr = args.pop().unwrap();
+                match name.as_str() {
+                    "Minus" => a.Negate(),
+                    "Sqrt" => a.Sqrt(),
+                    "Sin" => a.Sin(),
+                    "Cos" => a.Cos(),
+                    "ArcSin" => a.ASin(),
+                    "Log" => a.Log(),
+                    _ => a.ACos(),
+                }
+            }
+            _ => return Err(ParseError { column, message: format!("unknown head `{}`", name) }),
+        };
+        return Ok(result);
+    }
+}
+
+impl Expr {
+    pub fn to_full_form(&self) -> Result<String, ExportError> {
+        return write(self);
+    }
+
+    pub fn from_full_form(input: &str) -> Result<Expr, ParseError> {
+        let mut reader: Reader = Reader { chars: input.chars().collect(), pos: 0 };
+        let result: Expr = reader.expr()?;
+        reader.skip_whitespace();
+        if reader.pos < reader.chars.len() {
+            return Err(reader.error("unexpected trailing input"));
+        }
+        return Ok(result);
+    }
+}
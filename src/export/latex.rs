@@ -0,0 +1,234 @@
+use std::collections::*;
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::expr::print::*;
+use crate::mono::mono::*;
+use crate::poly::poly::Poly;
+use crate::coefficient::coefficient::Coefficient;
+
+// LaTeX rendering of `Expr`, `Mono` and `Poly`.
+//
+// Products are written by juxtaposition (`3 x^{2} y`) and fall back to `\cdot`
+// only where two numbers would otherwise run together. Division becomes
+// `\frac`, so it never needs parentheses around its operands. Coefficients of
+// `Mono` and `Poly` are written by `Coefficient::latex`, so a rational is a
+// `\frac` as well.
+
+// the greek letters LaTeX has commands for, capitals only where they differ from latin ones
+const GREEK: [&str; 34] = [
+    "alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta",
+    "iota", "kappa", "lambda", "mu", "nu", "xi", "pi", "rho",
+    "sigma", "tau", "upsilon", "phi", "chi", "psi", "omega",
+    "Gamma", "Delta", "Theta", "Lambda", "Xi", "Pi", "Sigma", "Upsilon",
+    "Phi", "Psi", "Omega",
+];
+
+fn variable(name: &str) -> String {
+    // x_1 stays a subscript, longer names are set upright unless they are greek letters
+    let stem: &str = name.split('_').next().unwrap();
+    if GREEK.contains(&stem) {
+        return name.replacen(stem, &format!("\\{}", stem), 1);
+    }
+    if stem.chars().count() > 1 {
+        return name.replacen(stem, &format!("\\mathrm{{{}}}", stem), 1);
+    }
+    return name.to_string();
+}
+
+fn number(n: &Number) -> String {
+    return n.to_string();
+}
+
+fn paren(s: String, needed: bool) -> String {
+    if needed {
+        return format!("\\left({}\\right)", s);
+    }
+    return s;
+}
+
+fn starts_with_digit(s: &str) -> bool {
+    return s.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '.');
+}
+
+// joins factors by juxtaposition, `\cdot` where a number follows another factor
+fn juxtapose(factors: Vec<String>) -> String {
+    let mut result: String = String::new();
+    for f in factors.into_iter() {
+        if result.is_empty() {
+            result = f;
+        }
+        else if starts_with_digit(&f) {
+            result = format!("{} \\cdot {}", result, f);
+        }
+        else {
+            result = format!("{} {}", result, f);
+        }
+    }
+    return result;
+}
+
+fn term_latex<C: Coefficient>(coefficient: &C, variables: &Variables) -> String {
+    let factors: Vec<String> = variables.iter()
+        .filter(|(_, n)| **n != 0.0)
+        .map(|(s, n)| {
+            if *n == 1.0 {
+                variable(s)
+            }
+            else {
+                format!("{}^{{{}}}", variable(s), number(n))
+            }
+        })
+        .collect();
+    if factors.is_empty() {
+        return coefficient.latex();
+    }
+    let body: String = juxtapose(factors);
+    if *coefficient == C::one() {
+        return body;
+    }
+    if *coefficient == -C::one() {
+        return format!("-{}", body);
+    }
+    return juxtapose(vec![coefficient.latex(), body]);
+}
+
+fn precedence(e: &Expr) -> u8 {
+    match e.op() {
+        EnumExpr::CONSTANT | EnumExpr::PARAM => term_precedence(&e.x),
+        EnumExpr::PLUS | EnumExpr::MINUS => SUM,
+        EnumExpr::TIMES | EnumExpr::DIV => PRODUCT,
+        EnumExpr::NEGATE => UNARY,
+        EnumExpr::POW | EnumExpr::SQUARE => POWER,
+        EnumExpr::INTEGRAL | EnumExpr::DEFINITE => SUM,
+        _ => ATOM,
+    }
+}
+
+fn function(op: &EnumExpr) -> String {
+    match op {
+        EnumExpr::SIN => String::from("\\sin"),
+        EnumExpr::COS => String::from("\\cos"),
+        EnumExpr::ASIN => String::from("\\arcsin"),
+        EnumExpr::ACOS => String::from("\\arccos"),
+        EnumExpr::LOG => String::from("\\ln"),
+        _ => format!("\\operatorname{{{}}}", op.to_string().to_lowercase()),
+    }
+}
+
+// factors of a product chain, flattened so `a*(b*c)` renders as `a b c`
+fn factors(e: &Expr, result: &mut Vec<String>) {
+    if e.op() == EnumExpr::TIMES {
+        factors(e.a.as_ref().unwrap(), result);
+        factors(e.b.as_ref().unwrap(), result);
+        return;
+    }
+    let own: String = write(e);
+    let needed: bool = precedence(e) < PRODUCT || (precedence(e) == UNARY && !result.is_empty());
+    result.push(paren(own, needed));
+}
+
+// `f \, dx` of an INTEGRAL node
+fn integrand(e: &Expr) -> String {
+    return format!("{} \\, d{}", write(e.a.as_ref().unwrap()), variable(&e.integration_variable()));
+}
+
+fn write(e: &Expr) -> String {
+    match e.op() {
+        EnumExpr::CONSTANT | EnumExpr::PARAM => term_latex(&e.x.coefficient, &e.x.variables),
+        EnumExpr::PLUS | EnumExpr::MINUS => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            let b: &Expr = e.b.as_ref().unwrap();
+            let right_paren: bool = precedence(b) == UNARY || (e.op() == EnumExpr::MINUS && precedence(b) == SUM);
+            let sign: &str = if e.op() == EnumExpr::PLUS { "+" } else { "-" };
+            format!("{} {} {}", write(a), sign, paren(write(b), right_paren))
+        }
+        EnumExpr::TIMES => {
+            let mut result: Vec<String> = Vec::new();
+            factors(e, &mut result);
+            juxtapose(result)
+        }
+        EnumExpr::DIV => format!("\\frac{{{}}}{{{}}}", write(e.a.as_ref().unwrap()), write(e.b.as_ref().unwrap())),
+        EnumExpr::POW => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            let base: String = paren(write(a), precedence(a) <= POWER || a.op() == EnumExpr::DIV);
+            format!("{}^{{{}}}", base, write(e.b.as_ref().unwrap()))
+        }
+        EnumExpr::SQUARE => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            let base: String = paren(write(a), precedence(a) <= POWER || a.op() == EnumExpr::DIV);
+            format!("{}^{{2}}", base)
+        }
+        EnumExpr::NEGATE => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            format!("-{}", paren(write(a), precedence(a) <= UNARY))
+        }
+        EnumExpr::SQRT => format!("\\sqrt{{{}}}", write(e.a.as_ref().unwrap())),
+        EnumExpr::INTEGRAL => format!("\\int {}", integrand(e)),
+        EnumExpr::DEFINITE => {
+            let (lower, upper) = e.bounds();
+            format!("\\int_{{{}}}^{{{}}} {}", write(lower), write(upper), integrand(e.a.as_ref().unwrap()))
+        }
+        _ => format!("{}\\left({}\\right)", function(&e.op()), write(e.a.as_ref().unwrap())),
+    }
+}
+
+fn poly_latex<C: Coefficient>(poly: &Poly<C>) -> String {
+    let monos: Vec<Mono<C>> = poly.sorted_monomials();
+    if monos.is_empty() {
+        return write(&poly.e());
+    }
+    let mut result: String = String::new();
+    for (i, mono) in monos.iter().enumerate() {
+        let c: C = mono.coefficient().clone();
+        if i == 0 {
+            result.push_str(&term_latex(&c, mono.variables()));
+        }
+        else if c.is_negative() {
+            result.push_str(&format!(" - {}", term_latex(&-c, mono.variables())));
+        }
+        else {
+            result.push_str(&format!(" + {}", term_latex(&c, mono.variables())));
+        }
+    }
+    return result;
+}
+
+fn align(lines: Vec<String>) -> String {
+    let mut result: String = String::from("\\begin{align}\n");
+    let n: usize = lines.len();
+    for (i, line) in lines.into_iter().enumerate() {
+        result.push_str(&line);
+        result.push_str(if i + 1 < n { " \\\\\n" } else { "\n" });
+    }
+    result.push_str("\\end{align}");
+    return result;
+}
+
+// one `p &= 0` row per polynomial, e.g. for a Gröbner basis
+pub fn basis_to_latex<C: Coefficient>(basis: &[Poly<C>]) -> String {
+    return align(basis.iter().map(|p| format!("{} &= 0", poly_latex(p))).collect());
+}
+
+// one `x &= value` row per variable of a solution
+pub fn solution_to_latex(solution: &BTreeMap<String, Expr>) -> String {
+    return align(solution.iter().map(|(k, v)| format!("{} &= {}", variable(k), write(v))).collect());
+}
+
+impl Expr {
+    pub fn to_latex(&self) -> String {
+        return write(self);
+    }
+}
+
+impl<C: Coefficient> Mono<C> {
+    pub fn to_latex(&self) -> String {
+        return term_latex(self.coefficient(), self.variables());
+    }
+}
+
+impl<C: Coefficient> Poly<C> {
+    pub fn to_latex(&self) -> String {
+        return poly_latex(self);
+    }
+}
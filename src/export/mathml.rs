@@ -0,0 +1,176 @@
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::expr::print::*;
+
+// Presentation MathML for `Expr`, for rendering in a browser.
+//
+// Operators go into `<mo>`, numbers into `<mn>`, variables into `<mi>`.
+// Products use the invisible times operator unless a number follows another
+// factor, parentheses follow the same precedence rules as the text printer.
+// Variable names are XML-escaped.
+
+const INVISIBLE_TIMES: &str = "<mo>&#x2062;</mo>";
+const DOT: &str = "<mo>&#x22C5;</mo>";
+const APPLY_FUNCTION: &str = "<mo>&#x2061;</mo>";
+const INTEGRAL: &str = "<mo>&#x222B;</mo>";
+const DIFFERENTIAL: &str = "<mo>&#x2146;</mo>";
+
+fn mn(n: &Number) -> String {
+    let val: f64 = f64::from(n.clone());
+    if val < 0.0 {
+        return format!("<mo>-</mo><mn>{}</mn>", -val);
+    }
+    return format!("<mn>{}</mn>", val);
+}
+
+fn escape(name: &str) -> String {
+    let mut result: String = String::new();
+    for c in name.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(c),
+        }
+    }
+    return result;
+}
+
+fn mi(name: &str) -> String {
+    return format!("<mi>{}</mi>", escape(name));
+}
+
+fn mrow(inner: String) -> String {
+    return format!("<mrow>{}</mrow>", inner);
+}
+
+fn fenced(inner: String, needed: bool) -> String {
+    if needed {
+        return mrow(format!("<mo>(</mo>{}<mo>)</mo>", inner));
+    }
+    return inner;
+}
+
+fn product(factors: Vec<(String, bool)>) -> String {
+    let mut result: String = String::new();
+    for (i, (f, numeric)) in factors.into_iter().enumerate() {
+        if i > 0 {
+            result.push_str(if numeric { DOT } else { INVISIBLE_TIMES });
+        }
+        result.push_str(&f);
+    }
+    return mrow(result);
+}
+
+fn term(x: &Term) -> String {
+    let mut factors: Vec<(String, bool)> = x.variables.iter()
+        .filter(|(_, n)| **n != 0.0)
+        .map(|(s, n)| {
+            if *n == 1.0 {
+                (mi(s), false)
+            }
+            else {
+                (format!("<msup>{}{}</msup>", mi(s), mrow(mn(n))), false)
+            }
+        })
+        .collect();
+    if factors.is_empty() {
+        return mn(&x.coefficient);
+    }
+    if x.coefficient == -1.0 {
+        return mrow(format!("<mo>-</mo>{}", product(factors)));
+    }
+    if x.coefficient != 1.0 {
+        factors.insert(0, (mn(&x.coefficient), true));
+    }
+    if factors.len() == 1 {
+        return factors.remove(0).0;
+    }
+    return product(factors);
+}
+
+fn precedence(e: &Expr) -> u8 {
+    match e.op() {
+        EnumExpr::CONSTANT | EnumExpr::PARAM => term_precedence(&e.x),
+        EnumExpr::PLUS | EnumExpr::MINUS => SUM,
+        EnumExpr::TIMES => PRODUCT,
+        EnumExpr::NEGATE => UNARY,
+        EnumExpr::POW | EnumExpr::SQUARE => POWER,
+        EnumExpr::INTEGRAL | EnumExpr::DEFINITE => SUM,
+        _ => ATOM,
+    }
+}
+
+fn starts_numeric(e: &Expr) -> bool {
+    match e.op() {
+        EnumExpr::CONSTANT => true,
+        EnumExpr::PARAM => e.x.coefficient != 1.0,
+        EnumExpr::TIMES | EnumExpr::POW => starts_numeric(e.a.as_ref().unwrap()),
+        _ => false,
+    }
+}
+
+fn function(op: &EnumExpr) -> &'static str {
+    match op {
+        EnumExpr::SIN => "sin",
+        EnumExpr::COS => "cos",
+        EnumExpr::ASIN => "arcsin",
+        EnumExpr::LOG => "ln",
+        _ => "arccos",
+    }
+}
+
+// `f dx` of an INTEGRAL node
+fn integrand(e: &Expr) -> String {
+    return format!("{}{}{}", write(e.a.as_ref().unwrap()), DIFFERENTIAL, mi(&e.integration_variable()));
+}
+
+fn write(e: &Expr) -> String {
+    match e.op() {
+        EnumExpr::CONSTANT | EnumExpr::PARAM => term(&e.x),
+        EnumExpr::PLUS | EnumExpr::MINUS => {
+            let b: &Expr = e.b.as_ref().unwrap();
+            let right_paren: bool = precedence(b) == UNARY || (e.op() == EnumExpr::MINUS && precedence(b) == SUM);
+            let sign: &str = if e.op() == EnumExpr::PLUS { "+" } else { "-" };
+            mrow(format!("{}<mo>{}</mo>{}", write(e.a.as_ref().unwrap()), sign, fenced(write(b), right_paren)))
+        }
+        EnumExpr::TIMES => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            let b: &Expr = e.b.as_ref().unwrap();
+            let lhs: String = fenced(write(a), precedence(a) < PRODUCT);
+            let rhs: String = fenced(write(b), precedence(b) <= UNARY);
+            product(vec![(lhs, false), (rhs, starts_numeric(b))])
+        }
+        EnumExpr::DIV => format!("<mfrac>{}{}</mfrac>", mrow(write(e.a.as_ref().unwrap())), mrow(write(e.b.as_ref().unwrap()))),
+        EnumExpr::POW | EnumExpr::SQUARE => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            let base: String = fenced(write(a), precedence(a) <= POWER);
+            let exponent: String = match e.op() {
+                EnumExpr::POW => write(e.b.as_ref().unwrap()),
+                _ => mn(&Number::Decimal(2.0)),
+            };
+            format!("<msup>{}{}</msup>", mrow(base), mrow(exponent))
+        }
+        EnumExpr::NEGATE => {
+            let a: &Expr = e.a.as_ref().unwrap();
+            mrow(format!("<mo>-</mo>{}", fenced(write(a), precedence(a) <= UNARY)))
+        }
+        EnumExpr::SQRT => format!("<msqrt>{}</msqrt>", mrow(write(e.a.as_ref().unwrap()))),
+        EnumExpr::INTEGRAL => mrow(format!("{}{}", INTEGRAL, integrand(e))),
+        EnumExpr::DEFINITE => {
+            let (lower, upper) = e.bounds();
+            let (lower, upper) = (mrow(write(lower)), mrow(write(upper)));
+            mrow(format!("<msubsup>{}{}{}</msubsup>{}", INTEGRAL, lower, upper, integrand(e.a.as_ref().unwrap())))
+        }
+        _ => mrow(format!("{}{}{}", mi(function(&e.op())), APPLY_FUNCTION, fenced(write(e.a.as_ref().unwrap()), true))),
+    }
+}
+
+impl Expr {
+    pub fn to_mathml(&self) -> String {
+        return format!("<math xmlns=\"http://www.w3.org/1998/Math/MathML\">{}</math>", write(self));
+    }
+}
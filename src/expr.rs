@@ -1 +1,21 @@
-pub mod expr;
\ No newline at end of file
+// lints the original module trips: SolveSpace-style names (`Plus`, `Eval`,
+// `PARAM_PTR`) and its older idioms
+#[allow(non_snake_case, non_camel_case_types)]
+#[allow(clippy::module_inception, clippy::needless_borrow, clippy::useless_conversion, clippy::needless_range_loop)]
+#[allow(clippy::needless_bool, clippy::op_ref, clippy::extra_unused_lifetimes)]
+pub mod expr;
+pub mod parse;
+pub mod diff;
+pub mod simplify;
+pub mod print;
+
+pub mod subs;
+pub mod integrate;
+pub mod series;
+pub mod limit;
+pub mod compile;
+pub mod batch;
+pub mod dual;
+pub mod tape;
+pub mod interval;
+pub mod complex;
\ No newline at end of file
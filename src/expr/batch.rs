@@ -0,0 +1,123 @@
+use std::fmt::Display;
+use ndarray::*;
+use num_notation::*;
+
+use crate::expr::expr::*;
+
+// Batch evaluation over ndarray columns.
+//
+// Every node is evaluated once for the whole batch: leaves become columns and
+// operators combine them elementwise, so the tree is walked once instead of
+// once per sample. Results match `Eval` sample by sample. Variables without a
+// column and columns of the wrong shape are reported as a `BatchError`.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchError {
+	pub node: String, // the expression or polynomial being evaluated, printed
+	pub message: String,
+}
+
+impl BatchError {
+	pub(crate) fn new(node: impl Display, message: String) -> Self {
+		BatchError { node: node.to_string(), message }
+	}
+}
+
+impl Display for BatchError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "`{}`: {}", self.node, self.message)
+	}
+}
+
+impl std::error::Error for BatchError {}
+
+pub(crate) struct Columns<'a, 'b> {
+	pub(crate) variables: &'a [&'a str],
+	pub(crate) columns: &'a [ArrayView1<'b, f64>],
+	pub(crate) len: usize,
+}
+
+impl<'a, 'b> Columns<'a, 'b> {
+	// `len` is the batch size, used when there are no columns
+	pub(crate) fn new(variables: &'a [&'a str], columns: &'a [ArrayView1<'b, f64>], len: usize) -> Result<Columns<'a, 'b>, String> {
+		if variables.len() != columns.len() {
+			return Err(format!("expected one column per variable, found {} variables and {} columns", variables.len(), columns.len()));
+		}
+		let len: usize = columns.first().map_or(len, |c| c.len());
+		if !columns.iter().all(|c| c.len() == len) {
+			return Err(String::from("columns differ in length"));
+		}
+		return Ok(Columns { variables, columns, len });
+	}
+
+	pub(crate) fn column(&self, name: &str) -> Option<&ArrayView1<'b, f64>> {
+		return self.variables.iter().position(|v| *v == name).map(|i| &self.columns[i]);
+	}
+
+	// the i-th sample as a map, for nodes that only `Eval` handles
+	fn sample(&self, i: usize) -> Variables {
+		return self.variables.iter().zip(self.columns.iter())
+			.map(|(v, c)| (v.to_string(), Number::Decimal(c[i])))
+			.collect();
+	}
+}
+
+fn eval_batch(e: &Expr, columns: &Columns) -> Result<Array1<f64>, BatchError> {
+	let result: Array1<f64> = match e.op {
+		EnumExpr::CONSTANT => Array1::from_elem(columns.len, f64::from(e.x.coefficient.clone())),
+		EnumExpr::PARAM => {
+			let mut result: Array1<f64> = Array1::ones(columns.len);
+			for (name, exp) in e.x.variables.iter() {
+				let exp: f64 = f64::from(exp.clone());
+				let column: &ArrayView1<f64> = match columns.column(name) {
+					Some(column) => column,
+					None => return Err(BatchError::new(e, format!("no column for variable {}", name))),
+				};
+				result.zip_mut_with(column, |r, &x| *r *= if exp == 1.0 { x } else { x.powf(exp) });
+			}
+			let coefficient: f64 = f64::from(e.x.coefficient.clone());
+			result.mapv_inplace(|r| r * coefficient);
+			result
+		}
+		EnumExpr::PLUS | EnumExpr::MINUS | EnumExpr::TIMES | EnumExpr::DIV | EnumExpr::POW => {
+			let mut a: Array1<f64> = eval_batch(e.a.as_ref().unwrap(), columns)?;
+			let b: Array1<f64> = eval_batch(e.b.as_ref().unwrap(), columns)?;
+			match e.op {
+				EnumExpr::PLUS => a += &b,
+				EnumExpr::MINUS => a -= &b,
+				EnumExpr::TIMES => a *= &b,
+				EnumExpr::DIV => a /= &b,
+				_ => a.zip_mut_with(&b, |x, &y| *x = x.powf(y)),
+			}
+			a
+		}
+		EnumExpr::NEGATE => -eval_batch(e.a.as_ref().unwrap(), columns)?,
+		EnumExpr::SQRT => eval_batch(e.a.as_ref().unwrap(), columns)?.sqrt(),
+		EnumExpr::SQUARE => eval_batch(e.a.as_ref().unwrap(), columns)?.powi(2),
+		EnumExpr::SIN => eval_batch(e.a.as_ref().unwrap(), columns)?.sin(),
+		EnumExpr::COS => eval_batch(e.a.as_ref().unwrap(), columns)?.cos(),
+		EnumExpr::ASIN => eval_batch(e.a.as_ref().unwrap(), columns)?.asin(),
+		EnumExpr::ACOS => eval_batch(e.a.as_ref().unwrap(), columns)?.acos(),
+		EnumExpr::LOG => eval_batch(e.a.as_ref().unwrap(), columns)?.ln(),
+		EnumExpr::INTEGRAL => Array1::from_elem(columns.len, f64::NAN),
+		// quadrature runs per sample
+		EnumExpr::DEFINITE => Array1::from_shape_fn(columns.len, |i| f64::from(e.Eval(&columns.sample(i)))),
+		_ => return Err(BatchError::new(e, format!("operation {} cannot be evaluated", e.op))),
+	};
+	return Ok(result);
+}
+
+impl Expr {
+	// evaluates at every sample, `columns[i]` holds the values of `variables[i]`
+	pub fn eval_array(&self, variables: &[&str], columns: &[ArrayView1<f64>]) -> Result<Array1<f64>, BatchError> {
+		let columns: Columns = Columns::new(variables, columns, 1).map_err(|message| BatchError::new(self, message))?;
+		return eval_batch(self, &columns);
+	}
+
+	// evaluates at every row of `points`, column i holds the values of `variables[i]`
+	pub fn eval_points(&self, variables: &[&str], points: ArrayView2<f64>) -> Result<Array1<f64>, BatchError> {
+		let columns: Vec<ArrayView1<f64>> = points.columns().into_iter().collect();
+		let columns: Columns = Columns::new(variables, &columns, points.nrows()).map_err(|message| BatchError::new(self, message))?;
+		return eval_batch(self, &columns);
+	}
+}
@@ -0,0 +1,184 @@
+use std::fmt::Display;
+
+use crate::expr::expr::*;
+
+// Compiled evaluation of an expression.
+//
+// The tree is flattened into a list of register instructions. Variables are
+// bound to slots in the order given to `compile`, so evaluating reads a plain
+// `&[f64]` instead of looking names up in a `Variables` map. Operands are
+// visited in Sethi-Ullman order, which keeps the number of registers at the
+// minimum the tree needs; evaluation uses a stack buffer and does not allocate
+// unless an expression needs more than `STACK_REGISTERS` of them. Integrals
+// and variables without a slot are not compiled, `compile` reports them.
+
+const STACK_REGISTERS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Instruction {
+	Constant { dst: usize, value: f64 },
+	// coefficient times values[slot]^exp for powers[start..end]
+	Term { dst: usize, coefficient: f64, start: usize, end: usize },
+	Binary { op: EnumExpr, dst: usize, lhs: usize, rhs: usize },
+	Unary { op: EnumExpr, dst: usize, src: usize },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileError {
+	pub node: String, // the subexpression that could not be compiled, printed
+	pub message: String,
+}
+
+impl CompileError {
+	pub(crate) fn new(e: &Expr, message: String) -> Self {
+		CompileError { node: e.to_string(), message }
+	}
+}
+
+impl Display for CompileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "`{}`: {}", self.node, self.message)
+	}
+}
+
+impl std::error::Error for CompileError {}
+
+#[derive(Debug, Clone)]
+pub struct CompiledExpr {
+	variables: Vec<String>,
+	code: Vec<Instruction>,
+	powers: Vec<(usize, f64)>,
+	registers: usize,
+}
+
+// registers needed to evaluate `e` when the costlier operand goes first
+fn need(e: &Expr) -> usize {
+	match e.children() {
+		0 => 1,
+		1 => need(e.a.as_ref().unwrap()),
+		_ => {
+			let (a, b) = (need(e.a.as_ref().unwrap()), need(e.b.as_ref().unwrap()));
+			if a == b { a + 1 } else { a.max(b) }
+		}
+	}
+}
+
+impl CompiledExpr {
+	fn emit(&mut self, e: &Expr, dst: usize) -> Result<(), CompileError> {
+		match e.op {
+			EnumExpr::CONSTANT => {
+				self.code.push(Instruction::Constant { dst, value: f64::from(e.x.coefficient.clone()) });
+			}
+			EnumExpr::PARAM => {
+				let start: usize = self.powers.len();
+				for (name, exp) in e.x.variables.iter() {
+					let slot: usize = match self.variables.iter().position(|v| v == name) {
+						Some(slot) => slot,
+						None => return Err(CompileError::new(e, format!("variable {} is not bound to a slot", name))),
+					};
+					self.powers.push((slot, f64::from(exp.clone())));
+				}
+				let end: usize = self.powers.len();
+				self.code.push(Instruction::Term { dst, coefficient: f64::from(e.x.coefficient.clone()), start, end });
+			}
+			EnumExpr::PLUS | EnumExpr::MINUS | EnumExpr::TIMES | EnumExpr::DIV | EnumExpr::POW => {
+				let (a, b) = (e.a.as_ref().unwrap(), e.b.as_ref().unwrap());
+				let (lhs, rhs) = if need(b) > need(a) {
+					self.emit(b, dst)?;
+					self.emit(a, dst + 1)?;
+					(dst + 1, dst)
+				}
+				else {
+					self.emit(a, dst)?;
+					self.emit(b, dst + 1)?;
+					(dst, dst + 1)
+				};
+				self.registers = self.registers.max(dst + 2);
+				self.code.push(Instruction::Binary { op: e.op.clone(), dst, lhs, rhs });
+			}
+			EnumExpr::NEGATE | EnumExpr::SQRT | EnumExpr::SQUARE | EnumExpr::SIN | EnumExpr::COS | EnumExpr::ASIN | EnumExpr::ACOS | EnumExpr::LOG => {
+				self.emit(e.a.as_ref().unwrap(), dst)?;
+				self.code.push(Instruction::Unary { op: e.op.clone(), dst, src: dst });
+			}
+			EnumExpr::INTEGRAL | EnumExpr::DEFINITE => return Err(CompileError::new(e, String::from("integrals cannot be compiled, use Eval"))),
+			_ => return Err(CompileError::new(e, format!("operation {} cannot be compiled", e.op))),
+		}
+		return Ok(());
+	}
+
+	pub fn variables(&self) -> &[String] {
+		return &self.variables;
+	}
+
+	// size of the scratch slice `eval_with` needs
+	pub fn registers(&self) -> usize {
+		return self.registers;
+	}
+
+	// `values[i]` is the value of `variables()[i]`
+	pub fn eval(&self, values: &[f64]) -> f64 {
+		if self.registers <= STACK_REGISTERS {
+			let mut registers: [f64; STACK_REGISTERS] = [0.0; STACK_REGISTERS];
+			return self.eval_with(values, &mut registers);
+		}
+		return self.eval_with(values, &mut vec![0.0; self.registers]);
+	}
+
+	// evaluates with caller provided scratch registers
+	pub fn eval_with(&self, values: &[f64], registers: &mut [f64]) -> f64 {
+		assert!(values.len() >= self.variables.len(), "expected {} values, got {}", self.variables.len(), values.len());
+		assert!(registers.len() >= self.registers, "expected {} registers, got {}", self.registers, registers.len());
+		for instruction in self.code.iter() {
+			match *instruction {
+				Instruction::Constant { dst, value } => registers[dst] = value,
+				Instruction::Term { dst, coefficient, start, end } => {
+					// same order of operations as Eval
+					let mut result: f64 = 1.0;
+					for &(slot, exp) in self.powers[start..end].iter() {
+						result *= if exp == 1.0 { values[slot] } else { values[slot].powf(exp) };
+					}
+					registers[dst] = result * coefficient;
+				}
+				Instruction::Binary { ref op, dst, lhs, rhs } => {
+					let (a, b) = (registers[lhs], registers[rhs]);
+					registers[dst] = match op {
+						EnumExpr::PLUS => a + b,
+						EnumExpr::MINUS => a - b,
+						EnumExpr::TIMES => a * b,
+						EnumExpr::DIV => a / b,
+						_ => a.powf(b),
+					};
+				}
+				Instruction::Unary { ref op, dst, src } => {
+					let a: f64 = registers[src];
+					registers[dst] = match op {
+						EnumExpr::NEGATE => -a,
+						EnumExpr::SQRT => a.sqrt(),
+						EnumExpr::SQUARE => a.powi(2),
+						EnumExpr::SIN => a.sin(),
+						EnumExpr::COS => a.cos(),
+						EnumExpr::ASIN => a.asin(),
+						EnumExpr::LOG => a.ln(),
+						_ => a.acos(),
+					};
+				}
+			}
+		}
+		return registers[0];
+	}
+}
+
+impl Expr {
+	// flattens the tree for repeated evaluation, `variables` fixes the slot of
+	// each variable in the values passed to `CompiledExpr::eval`
+	pub fn compile(&self, variables: &[&str]) -> Result<CompiledExpr, CompileError> {
+		let mut compiled: CompiledExpr = CompiledExpr {
+			variables: variables.iter().map(|v| v.to_string()).collect(),
+			code: Vec::new(),
+			powers: Vec::new(),
+			registers: 1,
+		};
+		compiled.emit(self, 0)?;
+		return Ok(compiled);
+	}
+}
@@ -0,0 +1,103 @@
+use std::collections::*;
+use num_complex::Complex;
+
+use crate::expr::expr::*;
+
+// Complex evaluation.
+//
+// Variables take complex values and every operation uses its principal
+// branch, so SQRT(-1) is i and ASIN(2) is pi/2 - i ln(2 + sqrt(3)) rather
+// than NaN. Exponents of PARAM terms may be any `Number`, integral ones are
+// taken by repeated multiplication and the others through the principal
+// logarithm.
+
+fn power(base: Complex<f64>, exp: f64) -> Complex<f64> {
+	if exp.fract() == 0.0 && exp.abs() <= i32::MAX as f64 {
+		return base.powi(exp as i32);
+	}
+	return base.powf(exp);
+}
+
+// adaptive Simpson rule along the segment from `lower` to `upper`
+fn quadrature(f: &dyn Fn(Complex<f64>) -> Complex<f64>, lower: Complex<f64>, upper: Complex<f64>) -> Complex<f64> {
+	let at = |s: f64| -> Complex<f64> {
+		return f(lower + (upper - lower) * s);
+	};
+	fn simpson(fa: Complex<f64>, fm: Complex<f64>, fb: Complex<f64>, h: f64) -> Complex<f64> {
+		return (fa + fm * 4.0 + fb) * (h / 6.0);
+	}
+	fn adapt(at: &dyn Fn(f64) -> Complex<f64>, (a, b): (f64, f64), f: (Complex<f64>, Complex<f64>, Complex<f64>), whole: Complex<f64>, tol: f64, depth: usize) -> Complex<f64> {
+		let (fa, fm, fb) = f;
+		let m: f64 = (a + b) / 2.0;
+		let (flm, frm) = (at((a + m) / 2.0), at((m + b) / 2.0));
+		let left: Complex<f64> = simpson(fa, flm, fm, m - a);
+		let right: Complex<f64> = simpson(fm, frm, fb, b - m);
+		if depth == 0 || (left + right - whole).norm() <= 15.0 * tol {
+			return left + right + (left + right - whole) / 15.0;
+		}
+		return adapt(at, (a, m), (fa, flm, fm), left, tol / 2.0, depth - 1) + adapt(at, (m, b), (fm, frm, fb), right, tol / 2.0, depth - 1);
+	}
+	let f: (Complex<f64>, Complex<f64>, Complex<f64>) = (at(0.0), at(0.5), at(1.0));
+	let whole: Complex<f64> = simpson(f.0, f.1, f.2, 1.0);
+	return adapt(&at, (0.0, 1.0), f, whole, 1e-10, 40) * (upper - lower);
+}
+
+fn eval_complex(e: &Expr, values: &BTreeMap<String, Complex<f64>>) -> Complex<f64> {
+	match e.op {
+		EnumExpr::CONSTANT => Complex::new(f64::from(e.x.coefficient.clone()), 0.0),
+		EnumExpr::PARAM => {
+			let mut result: Complex<f64> = Complex::new(1.0, 0.0);
+			for (key, exp) in e.x.variables.iter() {
+				match values.get(key) {
+					Some(value) => result *= power(*value, f64::from(exp.clone())),
+					None => panic!("no value for variable {}", key),
+				}
+			}
+			result * f64::from(e.x.coefficient.clone())
+		}
+		EnumExpr::PLUS => eval_complex(e.a.as_ref().unwrap(), values) + eval_complex(e.b.as_ref().unwrap(), values),
+		EnumExpr::MINUS => eval_complex(e.a.as_ref().unwrap(), values) - eval_complex(e.b.as_ref().unwrap(), values),
+		EnumExpr::TIMES => eval_complex(e.a.as_ref().unwrap(), values) * eval_complex(e.b.as_ref().unwrap(), values),
+		EnumExpr::DIV => eval_complex(e.a.as_ref().unwrap(), values) / eval_complex(e.b.as_ref().unwrap(), values),
+		EnumExpr::POW => {
+			let base: Complex<f64> = eval_complex(e.a.as_ref().unwrap(), values);
+			let exponent: Complex<f64> = eval_complex(e.b.as_ref().unwrap(), values);
+			if exponent.im == 0.0 {
+				power(base, exponent.re)
+			}
+			else {
+				base.powc(exponent)
+			}
+		}
+		EnumExpr::NEGATE => -eval_complex(e.a.as_ref().unwrap(), values),
+		EnumExpr::SQRT => eval_complex(e.a.as_ref().unwrap(), values).sqrt(),
+		EnumExpr::SQUARE => eval_complex(e.a.as_ref().unwrap(), values).powi(2),
+		EnumExpr::SIN => eval_complex(e.a.as_ref().unwrap(), values).sin(),
+		EnumExpr::COS => eval_complex(e.a.as_ref().unwrap(), values).cos(),
+		EnumExpr::ASIN => eval_complex(e.a.as_ref().unwrap(), values).asin(),
+		EnumExpr::ACOS => eval_complex(e.a.as_ref().unwrap(), values).acos(),
+		EnumExpr::LOG => eval_complex(e.a.as_ref().unwrap(), values).ln(),
+		EnumExpr::INTEGRAL => Complex::new(f64::NAN, f64::NAN),
+		EnumExpr::DEFINITE => {
+			let (lower, upper) = e.bounds();
+			let upper: Complex<f64> = eval_complex(upper, values);
+			let lower: Complex<f64> = eval_complex(lower, values);
+			let integrand: &Expr = e.a.as_ref().unwrap().a.as_ref().unwrap();
+			let x: String = e.integration_variable();
+			let f = |t: Complex<f64>| -> Complex<f64> {
+				let mut point: BTreeMap<String, Complex<f64>> = values.clone();
+				point.insert(x.clone(), t);
+				return eval_complex(integrand, &point);
+			};
+			quadrature(&f, lower, upper)
+		}
+		_ => panic!("Unknown operation"),
+	}
+}
+
+impl Expr {
+	// value at complex points, on the principal branch of every operation
+	pub fn eval_complex(&self, values: &BTreeMap<String, Complex<f64>>) -> Complex<f64> {
+		return eval_complex(self, values);
+	}
+}
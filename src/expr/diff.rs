@@ -0,0 +1,200 @@
+use std::collections::*;
+use num_notation::*;
+
+use crate::expr::expr::*;
+
+// Partial derivatives of an `Expr` tree.
+//
+// The rules build new nodes with the same `Plus`/`Times`/... constructors the
+// rest of the crate uses. Products and sums with a literal 0 or 1 are skipped
+// while building, otherwise every derivative of a leaf would leave `0 * x`
+// subtrees behind and higher order partials grow exponentially.
+
+fn constant(e: &Expr) -> Option<f64> {
+	if e.op == EnumExpr::CONSTANT || (e.op == EnumExpr::PARAM && e.x.variables.is_empty()) {
+		return Some(f64::from(e.x.coefficient.clone()));
+	}
+	return None;
+}
+
+fn is_value(e: &Expr, val: f64) -> bool {
+	match constant(e) {
+		Some(c) => c == val,
+		None => false,
+	}
+}
+
+fn add(a: Expr, b: Expr) -> Expr {
+	if is_value(&a, 0.0) {
+		return b;
+	}
+	if is_value(&b, 0.0) {
+		return a;
+	}
+	return a.Plus(b);
+}
+
+fn sub(a: Expr, b: Expr) -> Expr {
+	if is_value(&b, 0.0) {
+		return a;
+	}
+	if is_value(&a, 0.0) {
+		return b.Negate();
+	}
+	return a.Minus(b);
+}
+
+fn mul(a: Expr, b: Expr) -> Expr {
+	if is_value(&a, 0.0) || is_value(&b, 0.0) {
+		return Expr::from(0.0);
+	}
+	if is_value(&a, 1.0) {
+		return b;
+	}
+	if is_value(&b, 1.0) {
+		return a;
+	}
+	return a.Times(b);
+}
+
+fn div(a: Expr, b: Expr) -> Expr {
+	if is_value(&a, 0.0) {
+		return Expr::from(0.0);
+	}
+	if is_value(&b, 1.0) {
+		return a;
+	}
+	return a.Div(b);
+}
+
+fn neg(a: Expr) -> Expr {
+	if is_value(&a, 0.0) {
+		return a;
+	}
+	return a.Negate();
+}
+
+// d/dp of c * v1^e1 * ... * p^ep * ... = c*ep * v1^e1 * ... * p^(ep-1) * ...
+fn partial_term(x: &Term, p: &str) -> Expr {
+	let exponent: Number = match x.variables.get(p) {
+		Some(exp) => exp.clone(),
+		None => return Expr::from(0.0),
+	};
+	let mut variables: Variables = x.variables.clone();
+	let reduced: Number = exponent.clone() - Number::Decimal(1.0);
+	if reduced == 0.0 {
+		variables.remove(p);
+	}
+	else {
+		variables.insert(p.to_string(), reduced);
+	}
+	let coefficient: Number = x.coefficient.clone() * exponent;
+	if variables.is_empty() {
+		return Expr::from(coefficient);
+	}
+	return Expr::from((coefficient, variables));
+}
+
+impl Expr {
+	pub fn partial_wrt(&self, p: &str) -> Expr {
+		if !self.depends_on(p) {
+			return Expr::from(0.0);
+		}
+		match self.op {
+			EnumExpr::CONSTANT => Expr::from(0.0),
+			EnumExpr::PARAM => partial_term(&self.x, p),
+			EnumExpr::INTEGRAL | EnumExpr::DEFINITE => partial_integral(self, p),
+			_ => {
+				let a: Expr = self.a.as_ref().unwrap().as_ref().clone();
+				let da: Expr = a.partial_wrt(p);
+				match self.op {
+					EnumExpr::NEGATE => neg(da),
+					// d sqrt(a) = da / (2 sqrt(a))
+					EnumExpr::SQRT => div(da, mul(Expr::from(2.0), a.Sqrt())),
+					// d a^2 = 2 a da
+					EnumExpr::SQUARE => mul(mul(Expr::from(2.0), a), da),
+					EnumExpr::SIN => mul(a.Cos(), da),
+					EnumExpr::COS => neg(mul(a.Sin(), da)),
+					// d asin(a) = da / sqrt(1 - a^2), d acos(a) = -da / sqrt(1 - a^2)
+					EnumExpr::ASIN => div(da, Expr::from(1.0).Minus(a.Square()).Sqrt()),
+					EnumExpr::ACOS => neg(div(da, Expr::from(1.0).Minus(a.Square()).Sqrt())),
+					EnumExpr::LOG => div(da, a),
+					_ => {
+						let b: Expr = self.b.as_ref().unwrap().as_ref().clone();
+						let db: Expr = b.partial_wrt(p);
+						match self.op {
+							EnumExpr::PLUS => add(da, db),
+							EnumExpr::MINUS => sub(da, db),
+							EnumExpr::TIMES => add(mul(da, b.clone()), mul(a, db)),
+							// (da b - a db) / b^2
+							EnumExpr::DIV => div(sub(mul(da, b.clone()), mul(a, db)), b.Square()),
+							EnumExpr::POW => partial_pow(a, da, b, db),
+							_ => panic!("Unknown operation"),
+						}
+					}
+				}
+			}
+		}
+	}
+
+	// n-th partial derivative with respect to a single variable
+	pub fn partial_wrt_n(&self, p: &str, n: usize) -> Expr {
+		let mut result: Expr = self.clone();
+		for _ in 0..n {
+			result = result.partial_wrt(p);
+		}
+		return result;
+	}
+
+	// mixed partial, differentiating in the given order: `["x", "y"]` is d/dy (d/dx self)
+	pub fn mixed_partial(&self, vars: &[&str]) -> Expr {
+		let mut result: Expr = self.clone();
+		for p in vars.iter() {
+			result = result.partial_wrt(p);
+		}
+		return result;
+	}
+}
+
+fn partial_pow(a: Expr, da: Expr, b: Expr, db: Expr) -> Expr {
+	if is_value(&db, 0.0) {
+		// power rule: b a^(b-1) da
+		let reduced: Expr = match constant(&b) {
+			Some(c) => Expr::from(c - 1.0),
+			None => b.clone().Minus(Expr::from(1.0)),
+		};
+		return mul(mul(b, a.Pow(reduced)), da);
+	}
+	if let Some(c) = constant(&a) && is_value(&da, 0.0) {
+		// c^b with c constant: c^b ln(c) db
+		return mul(mul(a.Pow(b), Expr::from(c.ln())), db);
+	}
+	// a^b (db ln(a) + b da / a)
+	let power: Expr = a.clone().Pow(b.clone());
+	return mul(power, add(mul(db, a.clone().Log()), div(mul(b, da), a)));
+}
+
+// d/dp int f dx is f for p = x and int df/dp dx otherwise, a definite integral
+// follows the Leibniz rule f(upper) upper' - f(lower) lower' + int_lower^upper df/dp dx
+fn partial_integral(e: &Expr, p: &str) -> Expr {
+	let x: String = e.integration_variable();
+	if e.op == EnumExpr::INTEGRAL {
+		let integrand: &Expr = e.a.as_ref().unwrap();
+		if x == p {
+			return integrand.clone();
+		}
+		return integrand.partial_wrt(p).Integral(&x);
+	}
+	let integrand: &Expr = e.a.as_ref().unwrap().a.as_ref().unwrap();
+	let (lower, upper) = e.bounds();
+	let (lower, upper) = (lower.clone(), upper.clone());
+	// an integrand holding an indefinite integral in x has no value at the bounds
+	let at = |bound: Expr| -> Expr {
+		return integrand.subs(&BTreeMap::from([(x.clone(), bound)])).unwrap_or(Expr::from(f64::NAN));
+	};
+	let mut result: Expr = sub(mul(at(upper.clone()), upper.partial_wrt(p)), mul(at(lower.clone()), lower.partial_wrt(p)));
+	if integrand.depends_on(p) && x != p {
+		result = add(result, integrand.partial_wrt(p).Definite(&x, lower, upper));
+	}
+	return result;
+}
@@ -0,0 +1,202 @@
+use std::collections::*;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::expr::expr::*;
+
+// Forward mode automatic differentiation.
+//
+// A `Dual` carries a value and its gradient with respect to a fixed list of
+// variables. Each operation applies the chain rule to the whole gradient, so
+// one pass over the tree gives every partial derivative without building the
+// symbolic derivatives.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dual {
+	pub value: f64,
+	pub gradient: Vec<f64>,
+}
+
+impl Dual {
+	pub fn constant(value: f64, n: usize) -> Dual {
+		return Dual { value, gradient: vec![0.0; n] };
+	}
+
+	// the i-th of n variables
+	pub fn variable(value: f64, i: usize, n: usize) -> Dual {
+		let mut gradient: Vec<f64> = vec![0.0; n];
+		gradient[i] = 1.0;
+		return Dual { value, gradient };
+	}
+
+	// f(self) given f and f' at self.value
+	fn chain(mut self, value: f64, derivative: f64) -> Dual {
+		for d in self.gradient.iter_mut() {
+			*d *= derivative;
+		}
+		self.value = value;
+		return self;
+	}
+
+	fn is_constant(&self) -> bool {
+		return self.gradient.iter().all(|d| *d == 0.0);
+	}
+
+	pub fn sqrt(self) -> Dual {
+		let value: f64 = self.value.sqrt();
+		return self.chain(value, 0.5 / value);
+	}
+
+	pub fn square(self) -> Dual {
+		let x: f64 = self.value;
+		return self.chain(x.powi(2), 2.0 * x);
+	}
+
+	pub fn sin(self) -> Dual {
+		let x: f64 = self.value;
+		return self.chain(x.sin(), x.cos());
+	}
+
+	pub fn cos(self) -> Dual {
+		let x: f64 = self.value;
+		return self.chain(x.cos(), -x.sin());
+	}
+
+	pub fn asin(self) -> Dual {
+		let x: f64 = self.value;
+		return self.chain(x.asin(), 1.0 / (1.0 - x * x).sqrt());
+	}
+
+	pub fn acos(self) -> Dual {
+		let x: f64 = self.value;
+		return self.chain(x.acos(), -1.0 / (1.0 - x * x).sqrt());
+	}
+
+	pub fn ln(self) -> Dual {
+		let x: f64 = self.value;
+		return self.chain(x.ln(), 1.0 / x);
+	}
+
+	pub fn powf(self, n: f64) -> Dual {
+		if n == 1.0 {
+			return self;
+		}
+		let x: f64 = self.value;
+		return self.chain(x.powf(n), n * x.powf(n - 1.0));
+	}
+
+	pub fn pow(self, exponent: Dual) -> Dual {
+		if exponent.is_constant() {
+			return self.powf(exponent.value);
+		}
+		// d(a^b) = b a^(b - 1) da + a^b ln(a) db
+		let value: f64 = self.value.powf(exponent.value);
+		let da: f64 = if self.is_constant() { 0.0 } else { exponent.value * self.value.powf(exponent.value - 1.0) };
+		let db: f64 = value * self.value.ln();
+		let gradient: Vec<f64> = self.gradient.iter().zip(exponent.gradient.iter()).map(|(a, b)| da * a + db * b).collect();
+		return Dual { value, gradient };
+	}
+}
+
+impl Add for Dual {
+	type Output = Dual;
+	fn add(mut self, other: Dual) -> Dual {
+		self.value += other.value;
+		for (d, o) in self.gradient.iter_mut().zip(other.gradient.iter()) {
+			*d += o;
+		}
+		return self;
+	}
+}
+
+impl Sub for Dual {
+	type Output = Dual;
+	fn sub(mut self, other: Dual) -> Dual {
+		self.value -= other.value;
+		for (d, o) in self.gradient.iter_mut().zip(other.gradient.iter()) {
+			*d -= o;
+		}
+		return self;
+	}
+}
+
+impl Mul for Dual {
+	type Output = Dual;
+	fn mul(mut self, other: Dual) -> Dual {
+		for (d, o) in self.gradient.iter_mut().zip(other.gradient.iter()) {
+			*d = *d * other.value + self.value * o;
+		}
+		self.value *= other.value;
+		return self;
+	}
+}
+
+impl Div for Dual {
+	type Output = Dual;
+	fn div(mut self, other: Dual) -> Dual {
+		let square: f64 = other.value * other.value;
+		for (d, o) in self.gradient.iter_mut().zip(other.gradient.iter()) {
+			*d = (*d * other.value - self.value * o) / square;
+		}
+		self.value /= other.value;
+		return self;
+	}
+}
+
+impl Neg for Dual {
+	type Output = Dual;
+	fn neg(mut self) -> Dual {
+		self.value = -self.value;
+		for d in self.gradient.iter_mut() {
+			*d = -*d;
+		}
+		return self;
+	}
+}
+
+fn eval_dual(e: &Expr, values: &Variables, variables: &[String]) -> Dual {
+	let n: usize = variables.len();
+	match e.op {
+		EnumExpr::CONSTANT => Dual::constant(f64::from(e.x.coefficient.clone()), n),
+		EnumExpr::PARAM => {
+			let mut result: Dual = Dual::constant(1.0, n);
+			for (key, exp) in e.x.variables.iter() {
+				let value: f64 = match values.get(key) {
+					Some(value) => f64::from(value.clone()),
+					None => panic!("no value for variable {}", key),
+				};
+				let i: usize = variables.iter().position(|v| v == key).unwrap();
+				result = result * Dual::variable(value, i, n).powf(f64::from(exp.clone()));
+			}
+			result * Dual::constant(f64::from(e.x.coefficient.clone()), n)
+		}
+		EnumExpr::PLUS => eval_dual(e.a.as_ref().unwrap(), values, variables) + eval_dual(e.b.as_ref().unwrap(), values, variables),
+		EnumExpr::MINUS => eval_dual(e.a.as_ref().unwrap(), values, variables) - eval_dual(e.b.as_ref().unwrap(), values, variables),
+		EnumExpr::TIMES => eval_dual(e.a.as_ref().unwrap(), values, variables) * eval_dual(e.b.as_ref().unwrap(), values, variables),
+		EnumExpr::DIV => eval_dual(e.a.as_ref().unwrap(), values, variables) / eval_dual(e.b.as_ref().unwrap(), values, variables),
+		EnumExpr::POW => eval_dual(e.a.as_ref().unwrap(), values, variables).pow(eval_dual(e.b.as_ref().unwrap(), values, variables)),
+		EnumExpr::NEGATE => -eval_dual(e.a.as_ref().unwrap(), values, variables),
+		EnumExpr::SQRT => eval_dual(e.a.as_ref().unwrap(), values, variables).sqrt(),
+		EnumExpr::SQUARE => eval_dual(e.a.as_ref().unwrap(), values, variables).square(),
+		EnumExpr::SIN => eval_dual(e.a.as_ref().unwrap(), values, variables).sin(),
+		EnumExpr::COS => eval_dual(e.a.as_ref().unwrap(), values, variables).cos(),
+		EnumExpr::ASIN => eval_dual(e.a.as_ref().unwrap(), values, variables).asin(),
+		EnumExpr::ACOS => eval_dual(e.a.as_ref().unwrap(), values, variables).acos(),
+		EnumExpr::LOG => eval_dual(e.a.as_ref().unwrap(), values, variables).ln(),
+		EnumExpr::INTEGRAL => Dual { value: f64::NAN, gradient: vec![f64::NAN; variables.len()] },
+		// the Leibniz rule is applied symbolically, the integrals by quadrature
+		EnumExpr::DEFINITE => Dual {
+			value: f64::from(e.Eval(values)),
+			gradient: variables.iter().map(|v| f64::from(e.partial_wrt(v).Eval(values))).collect(),
+		},
+		_ => panic!("Unknown operation"),
+	}
+}
+
+impl Expr {
+	// value and partial derivatives with respect to every free variable
+	pub fn eval_with_gradient(&self, values: &Variables) -> (f64, BTreeMap<String, f64>) {
+		let variables: Vec<String> = self.free_variables().into_iter().collect();
+		let result: Dual = eval_dual(self, values, &variables);
+		return (result.value, variables.into_iter().zip(result.gradient).collect());
+	}
+}
@@ -7,8 +7,11 @@ use num_notation::*;
 use num_traits::Pow;
 use std::hash::*; // hash for expr needs to be implemented
 use crate::mono::mono::*;
+use crate::expr::print::PrintStyle;
+use crate::expr::integrate::quadrature;
+use crate::coefficient::coefficient::Coefficient;
 
-struct symbol (HashMap<String, String>);
+pub(crate) struct symbol (pub(crate) HashMap<String, String>);
 
 impl <'a> Default for symbol {
 	fn default() -> Self {
@@ -24,12 +27,14 @@ impl <'a> Default for symbol {
 			(String::from("COS"),		String::from("Cos")),
 			(String::from("ASIN"),		String::from("Asin")),
 			(String::from("ACOS"),		String::from("Acos")),
+			(String::from("LOG"),		String::from("Log")),
+			(String::from("INTEGRAL"),	String::from("Integrate")),
 			(String::from("POWER"),		String::from("^")),
 		]))
 	}
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EnumExpr {
 	PARAM		= 0,
 	PARAM_PTR	= 1,
@@ -48,6 +53,9 @@ pub enum EnumExpr {
 	COS 		= 109,
 	ASIN		= 110,
 	ACOS 		= 111,
+	INTEGRAL	= 112, // unevaluated integral of `a` with respect to the variable `b`
+	DEFINITE	= 113, // `a` is an INTEGRAL from the lower bound `b` to the upper bound `c`
+	LOG			= 114, // natural logarithm
 
 	ALL_RESOLVED= 1000,
 	PAREN		= 1001,
@@ -75,6 +83,9 @@ impl Display for EnumExpr {
 			(109,       "COS"),
 			(110,       "ASIN"),
 			(111,       "ACOS"),
+			(112,       "INTEGRAL"),
+			(113,       "DEFINITE"),
+			(114,       "LOG"),
 			(1000,      "ALL_RESOLVED"),
 			(1001,      "PAREN"),
 			(1002,      "BINARY_OP"),
@@ -138,10 +149,10 @@ impl Powi for Number {
 
 pub type Variables = BTreeMap<String, Number>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Term {
-	coefficient: Number,
-	variables: Variables
+	pub(crate) coefficient: Number,
+	pub(crate) variables: Variables
 }
 
 impl Term {
@@ -165,19 +176,40 @@ impl Display for XExpr {
 
 impl Display for Term {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		if self.variables.is_empty(){
-			write!(f, "{}", self.coefficient)
-		}
-		else {
-			write!(f, "{}", self.coefficient)?;
-			for (s, n) in self.variables.iter() {
-				write!(f, "*{}^{}", s, n)?;
-			}
-			Ok(())
+		write!(f, "{}", self.pretty(PrintStyle::Ascii))
+	}
+}
+
+// Hashes the numeric value so that hashing agrees with `Number`'s `PartialEq`;
+// -0.0 and 0.0 compare equal and therefore hash the same.
+pub(crate) fn hash_number<H: Hasher>(n: &Number, state: &mut H) {
+	let val: f64 = f64::from(n.clone());
+	let bits: u64 = if val == 0.0 { 0 } else { val.to_bits() };
+	bits.hash(state);
+}
+
+impl Hash for Term {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		hash_number(&self.coefficient, state);
+		for (s, n) in self.variables.iter() {
+			s.hash(state);
+			hash_number(n, state);
 		}
 	}
 }
 
+impl From<(Number, Variables)> for Term {
+	fn from((c, v): (Number, Variables)) -> Self {
+		Term { coefficient: c, variables: v }
+	}
+}
+
+impl<C: Coefficient> From<Mono<C>> for Term {
+	fn from(m: Mono<C>) -> Self {
+		Term { coefficient: m.coefficient().to_number(), variables: m.variables().clone() }
+	}
+}
+
 impl Default for Term {
 	fn default() -> Self {
 		Self { coefficient: (Number::Decimal(0.0)), variables: (Default::default()) }
@@ -193,23 +225,45 @@ pub enum poly_Check{
 #[derive(Clone, Debug)]
 pub struct Expr {
     // PARAM: f64,
-    a: Option<Arc<Expr>>,
-    b: Option<Arc<Expr>>,
-    op: EnumExpr,
-    x: Term,
+    pub(crate) a: Option<Arc<Expr>>,
+    pub(crate) b: Option<Arc<Expr>>,
+    pub(crate) c: Option<Arc<Expr>>, // third child, only DEFINITE has one
+    pub(crate) op: EnumExpr,
+    pub(crate) x: Term,
 }
 
+// Structural equality: same node kinds, same leaf terms, same children.
+// Use `equivalent` to compare mathematically equal but differently built trees.
 impl PartialEq for Expr {
 	fn eq(&self, other: &Expr) -> bool{
-		format!("{}", self) == format!("{}", other)
+		if self.op != other.op {
+			return false;
+		}
+		match self.children() {
+			0 => self.x == other.x,
+			1 => same_child(&self.a, &other.a),
+			2 => same_child(&self.a, &other.a) && same_child(&self.b, &other.b),
+			_ => same_child(&self.a, &other.a) && same_child(&self.b, &other.b) && same_child(&self.c, &other.c),
+		}
 	}
 }
 
+fn same_child(a: &Option<Arc<Expr>>, b: &Option<Arc<Expr>>) -> bool {
+	match (a, b) {
+		(Some(a), Some(b)) => Arc::ptr_eq(a, b) || a.as_ref() == b.as_ref(),
+		(None, None) => true,
+		_ => false,
+	}
+}
+
+impl Eq for Expr {}
+
 impl Default for Expr {
     fn default() -> Self {
         Expr {
             a: None,
             b: None,
+            c: None,
             op: EnumExpr::PARAM,
             x: Default::default(),
         }
@@ -218,79 +272,26 @@ impl Default for Expr {
 
 impl Display for Expr {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		// formatting based on operation and children
-		let enum_op:HashMap<usize, &str> = HashMap::from([
-			(0,			"PARAM"),
-			(1,			"PARAM_PTR"),
-			(10,		"POINT"),
-			(11,		"ENTITY"),
-			(20,		"CONSTANT"),
-			(100,		"PLUS"),
-			(101,		"MINUS"),
-			(102,		"TIMES"),
-			(103,		"DIV"),
-			(104,       "POW"),
-			(105,		"NEGATE"),
-			(106,		"SQRT"),
-			(107,		"SQUARE"),
-			(108,		"SIN"),
-			(109,		"COS"),
-			(110,		"ASIN"),
-			(111,		"ACOS"),
-			(1000,		"ALL_RESOLVED"),
-			(1001,		"PAREN"),
-			(1002,		"BINARY_OP"),
-			(1003,		"UNARY_OP"),
-		]);
-		let op_symbols:HashMap<String, String> = HashMap::from([
-			(String::from("PLUS"),		String::from("+")),
-			(String::from("MINUS"),		String::from("-")),
-			(String::from("TIMES"),		String::from("*")),
-			(String::from("DIV"),		String::from("/")),
-			(String::from("NEGATE"),	String::from("~")),
-			(String::from("SQRT"),		String::from("Sqrt")),
-			(String::from("SQUARE"),	String::from("Sqr")),
-			(String::from("SIN"),		String::from("Sin")),
-			(String::from("COS"),		String::from("Cos")),
-			(String::from("ASIN"),		String::from("Asin")),
-			(String::from("ACOS"),		String::from("Acos")),
-			(String::from("POWER"),		String::from("^")),
-
-		]);
-		if self.children() == 0 && self.op == EnumExpr::CONSTANT {
-			return write!(f, "{}", self.x);
-		}
-		else if self.children() == 0 && self.op == EnumExpr::PARAM {
-			return write!(f, "{}", self.x)
-		}
-		else {
-			if self.children() == 2 {
-				// Unary operation formatting
-				let str_a: String = self.a.clone().unwrap().to_string();
-				let str_b: String = self.b.clone().unwrap().to_string();
-				let op_string: String = String::from(*enum_op.get(&(self.op.clone() as usize)).unwrap());
-				let sym: &String = op_symbols.get(&op_string).unwrap();
-				return write!(f, "{} {} {}", str_a, sym, str_b);
-			}
-			else if self.children() == 1 {
-				// Binary operation formatting
-				let str_a: String = self.a.clone().unwrap().to_string();
-				let op_string: String = String::from(*enum_op.get(&(self.op.clone() as usize)).unwrap());
-				let sym: &String = op_symbols.get(&op_string).unwrap();
-				return write!(f, "{} {}", sym, str_a);
-			}
-			else{
-				return write!(f, "Unknown Expression");
-			}
-		}
+		// formatting based on operation precedence, see `expr::print`
+		write!(f, "{}", self.pretty(PrintStyle::Ascii))
 	}
 }
 
 impl Hash for Expr {
     // Note: No generic parameters for Self here
     fn hash<H: Hasher>(&self, state: &mut H) {
-		let fmt_expr = format!("{}", self); 
-		fmt_expr.hash(state);
+		self.op.hash(state);
+		match self.children() {
+			0 => self.x.hash(state),
+			1 => self.a.as_ref().unwrap().hash(state),
+			n => {
+				self.a.as_ref().unwrap().hash(state);
+				self.b.as_ref().unwrap().hash(state);
+				if n == 3 {
+					self.c.as_ref().unwrap().hash(state);
+				}
+			}
+		}
     }
 }
 
@@ -299,6 +300,7 @@ impl From<f64> for Expr {
 		Expr {
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::CONSTANT,
 			x: Term {
 				coefficient: Number::Decimal(value),
@@ -313,6 +315,7 @@ impl From<&str> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: Number::Decimal(1.0),
@@ -327,6 +330,7 @@ impl From<String> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: Number::Decimal(1.0),
@@ -341,6 +345,7 @@ impl From<Number> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::CONSTANT,
 			x: Term {
 				coefficient: n,
@@ -355,6 +360,7 @@ impl From<(String, Number)> for Expr{
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: Number::Decimal(1.0),
@@ -369,6 +375,7 @@ impl From<(Number, String, Number)> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: c,
@@ -383,6 +390,7 @@ impl From<(Number, Vec<String>, Vec<Number>)> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: c,
@@ -397,20 +405,22 @@ impl From<Term> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: c
 		}
 	}
 }
 
-impl From<Mono> for Expr {
-	fn from(m: Mono) -> Self{
+impl<C: Coefficient> From<Mono<C>> for Expr {
+	fn from(m: Mono<C>) -> Self{
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
-				coefficient: m.coefficient().clone(),
+				coefficient: m.coefficient().to_number(),
 				variables: m.variables().clone(),
 			}
 		}
@@ -427,6 +437,7 @@ impl From<(Vec<Number>, Vec<String>, Vec<Number>)> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: res,
@@ -442,6 +453,7 @@ impl From<(Number, Variables)> for Expr {
 			Expr{
 			a: None,
 			b: None,
+			c: None,
 			op: EnumExpr::PARAM,
 			x: Term {
 				coefficient: c,
@@ -451,8 +463,8 @@ impl From<(Number, Variables)> for Expr {
 	}
 }
 
-impl From<HashMap<String, Mono>> for Expr{
-	fn from(mono_map: HashMap<String, Mono>) -> Self {
+impl<C: Coefficient> From<HashMap<String, Mono<C>>> for Expr{
+	fn from(mono_map: HashMap<String, Mono<C>>) -> Self {
 		let mut exprs: Vec<Expr> = Vec::new();
 		for mono in mono_map.values() { 
 			let mono_expr: Expr = mono.e();
@@ -478,8 +490,8 @@ impl From<HashMap<String, Mono>> for Expr{
 	}
 }
 
-impl From<BTreeMap<String, Mono>> for Expr{
-	fn from(mono_map: BTreeMap<String, Mono>) -> Self {
+impl<C: Coefficient> From<BTreeMap<String, Mono<C>>> for Expr{
+	fn from(mono_map: BTreeMap<String, Mono<C>>) -> Self {
 		let mut exprs: Vec<Expr> = Vec::new();
 		for mono in mono_map.values() { 
 			let mono_expr: Expr = mono.e();
@@ -514,6 +526,10 @@ impl Expr {
 		self.b.clone()
 	}
 
+	pub fn c(&self) ->  Option<Arc<Expr>> {
+		self.c.clone()
+	}
+
 	pub fn config_mono(&self) -> Term {
 
 		let recurse = |mut xterm: Term| -> Term  {
@@ -559,16 +575,23 @@ impl Expr {
 				}
 			}
 			else if self.op == EnumExpr::TIMES {
-				let _left: Arc<Expr> = self.a.clone().unwrap();
+				let left: Arc<Expr> = self.a.clone().unwrap();
 				let right: Arc<Expr> = self.b.clone().unwrap();
 
-				let l_term: Term = right.config_mono();
-				let r_term = right.config_mono();
+				let l_term: Term = left.config_mono();
+				let r_term: Term = right.config_mono();
 
+				// exponents of shared variables add up, coefficients multiply
 				xterm.variables.extend(l_term.variables);
-				xterm.variables.extend(r_term.variables);
+				for (s, n) in r_term.variables.into_iter() {
+					let exp: Number = match xterm.variables.get(&s) {
+						Some(m) => m.clone() + n,
+						None => n,
+					};
+					xterm.variables.insert(s, exp);
+				}
 
-				xterm.coefficient += l_term.coefficient + r_term.coefficient;
+				xterm.coefficient *= l_term.coefficient * r_term.coefficient;
 				xterm
 			}
 			else {
@@ -576,7 +599,7 @@ impl Expr {
 			}
 		};
 
-		let xterm: Term = Default::default();
+		let xterm: Term = Term { coefficient: Number::Decimal(1.0), ..Default::default() };
 		recurse(xterm)
 		// return xterm;
 	}
@@ -591,6 +614,9 @@ impl Expr {
 			EnumExpr::MINUS => 2,
 			EnumExpr::TIMES => 2,
 			EnumExpr::DIV => 2,
+			EnumExpr::POW => 2,
+			EnumExpr::INTEGRAL => 2,
+			EnumExpr::DEFINITE => 3,
 			EnumExpr::NEGATE => 1,
 			EnumExpr::SQRT => 1,
 			EnumExpr::SQUARE => 1,
@@ -598,6 +624,7 @@ impl Expr {
 			EnumExpr::COS => 1,
 			EnumExpr::ASIN => 1,
 			EnumExpr::ACOS => 1,
+			EnumExpr::LOG => 1,
 			_ => panic!("Unknown operation"),
 		}
     }
@@ -607,7 +634,7 @@ impl Expr {
             0 => 1,
             1 => 1 + self.a.clone().unwrap().nodes(),
             2 => 1 + self.a.clone().unwrap().nodes() + &self.b.clone().unwrap().nodes(),
-            _ => panic!("Unknown operation")
+            _ => 1 + self.a.clone().unwrap().nodes() + self.b.clone().unwrap().nodes() + self.c.clone().unwrap().nodes(),
         }
     }
 
@@ -646,7 +673,7 @@ impl Expr {
 		// Implementation for polynomial_gme
 		if self.monomial_gme() {
 			let mut poly_map: HashMap<String, Mono> = HashMap::new();
-			poly_map.insert(self.to_string(), Mono::from((self.clone(), Some(self.to_string()))));
+			poly_map.insert(self.to_string(), Mono::from((self.clone(), None)));
 			return Some(poly_map);
 		}
 		else {
@@ -712,6 +739,10 @@ impl Expr {
 		return self.AnyBinOp(EnumExpr::DIV, b);
 	}
 
+	pub fn Pow (self, b: Expr) -> Expr {
+		return self.AnyBinOp(EnumExpr::POW, b);
+	}
+
 	pub fn Negate (self) -> Expr {
 		return self.AnyUnaOp(EnumExpr::NEGATE);
 	}
@@ -740,58 +771,34 @@ impl Expr {
 		return self.AnyUnaOp(EnumExpr::ACOS);
 	}
 
-	// fn PartialWrt (self, p: String) -> Expr {
-	// 	let da: Expr; = Expr::default(); // default should be 0.0
-	// 	let db: = Expr::default();
-
-	// 	match self.children() {
-	// 		0 => match self.op {
-	// 			EnumExpr::CONSTANT => {
-	// 				return Expr::new(XExpr::v((0.0)));
-	// 			}
-	// 			EnumExpr::PARAM => {
-	// 				if self.x == p {
-	// 					return Expr::new(XExpr::v((1.0)));
-	// 				}
-	// 				else {
-	// 					return Expr::new(XExpr::v((0.0)));
-	// 				}
-	// 			}
-	// 			_ => panic!("Unknown operation"),
-	// 		}
-	// 		1 => match self.op {
-	// 			EnumExpr::SIN =>
-	// 				return da.Times(self.a.Cos()),
-	// 			EnumExpr::
-	// 			EnumExpr::PARAM => {
-	// 				if self.x == p {
-	// 					return Expr::new(XExpr::v((1.0)));
-	// 				}
-	// 				else {
-	// 					return Expr::new(XExpr::v((0.0)));
-	// 				}
-	// 			}
-	// 			_ => panic!("Unknown operation"),
-	// 		}
-	// 		2 => match self.op {
-	// 			enum_Expr::CONSTANT => {
-	// 				return Expr::default(0.0);
-	// 			}
-	// 			enum_Expr::PARAM => {
-	// 				if self.x.parh == p {
-	// 					return Expr::default(1.0);
-	// 				}
-	// 				else {
-	// 					return Expr::default(0.0);
-	// 				}
-	// 			}
-	// 			_ => {
-	// 				panic!("Unknown operation"),
-	// 			}
-	// 		}
-	// 		_ => panic!("Unknown operation"),
-	// 	}
-	// }	
+	pub fn Log (self) -> Expr {
+		return self.AnyUnaOp(EnumExpr::LOG);
+	}
+
+	pub fn Integral (self, x: &str) -> Expr {
+		return self.AnyBinOp(EnumExpr::INTEGRAL, Expr::from(x));
+	}
+
+	pub fn Definite (self, x: &str, lower: Expr, upper: Expr) -> Expr {
+		return Self {
+			c: Some(Arc::new(upper)),
+			..self.Integral(x).AnyBinOp(EnumExpr::DEFINITE, lower)
+		};
+	}
+
+	// lower and upper bound of a DEFINITE node
+	pub(crate) fn bounds(&self) -> (&Expr, &Expr) {
+		return (self.b.as_ref().unwrap(), self.c.as_ref().unwrap());
+	}
+
+	// variable of an INTEGRAL or DEFINITE node
+	pub(crate) fn integration_variable(&self) -> String {
+		let node: &Expr = match self.op {
+			EnumExpr::DEFINITE => self.a.as_ref().unwrap(),
+			_ => self,
+		};
+		return node.b.as_ref().unwrap().x.variables.keys().next().unwrap().clone();
+	}
 
 	pub fn Eval (&self, values :&Variables) -> Number {
 		match self.op {
@@ -810,10 +817,15 @@ impl Expr {
 				result *= self.x.coefficient.clone();
 				result
 			}
-			EnumExpr::PLUS => self.a.as_ref().unwrap().Eval(&values),
+			EnumExpr::PLUS => self.a.as_ref().unwrap().Eval(&values) + self.b.as_ref().unwrap().Eval(&values),
 			EnumExpr::MINUS => self.a.as_ref().unwrap().Eval(&values) - self.b.as_ref().unwrap().Eval(&values),
-			EnumExpr::TIMES => self.a.as_ref().unwrap().Eval(&values) * self.a.as_ref().unwrap().Eval(&values),
+			EnumExpr::TIMES => self.a.as_ref().unwrap().Eval(&values) * self.b.as_ref().unwrap().Eval(&values),
 			EnumExpr::DIV => self.a.as_ref().unwrap().Eval(&values) / self.b.as_ref().unwrap().Eval(&values),
+			EnumExpr::POW => {
+				let base: f64 = f64::from(self.a.as_ref().unwrap().Eval(&values));
+				let exponent: f64 = f64::from(self.b.as_ref().unwrap().Eval(&values));
+				Number::Decimal(base.powf(exponent))
+			}
 			EnumExpr::NEGATE => -self.a.as_ref().unwrap().Eval(&values),
 			EnumExpr::SQRT => self.a.as_ref().unwrap().Eval(&values).sqrt(),
 			EnumExpr::SQUARE => self.a.as_ref().unwrap().Eval(&values).powi(2),
@@ -821,10 +833,91 @@ impl Expr {
 			EnumExpr::COS => self.a.as_ref().unwrap().Eval(&values).cos(),
 			EnumExpr::ASIN => self.a.as_ref().unwrap().Eval(&values).asin(),
 			EnumExpr::ACOS => self.a.as_ref().unwrap().Eval(&values).acos(),
+			EnumExpr::LOG => Number::Decimal(f64::from(self.a.as_ref().unwrap().Eval(values)).ln()),
+			// an indefinite integral has no value, see `try_eval`
+			EnumExpr::INTEGRAL => Number::Decimal(f64::NAN),
+			EnumExpr::DEFINITE => {
+				let (lower, upper) = self.bounds();
+				let (lower, upper) = (f64::from(lower.Eval(values)), f64::from(upper.Eval(values)));
+				let integral: &Expr = self.a.as_ref().unwrap();
+				Number::Decimal(quadrature(integral.a.as_ref().unwrap(), &self.integration_variable(), lower, upper, values))
+			}
 			_ => panic!("Unknown operation"),
 		}
 	}
 
+	// `Eval`, or None when a variable has no value or the tree holds an
+	// indefinite integral
+	pub fn try_eval(&self, values: &Variables) -> Option<Number> {
+		if !self.free_variables().iter().all(|s| values.contains_key(s)) || self.has_indefinite_integral() {
+			return None;
+		}
+		return Some(self.Eval(values));
+	}
+
+	fn has_indefinite_integral(&self) -> bool {
+		match self.children() {
+			0 => false,
+			_ if self.op == EnumExpr::INTEGRAL => true,
+			1 => self.a.as_ref().unwrap().has_indefinite_integral(),
+			2 => self.a.as_ref().unwrap().has_indefinite_integral() || self.b.as_ref().unwrap().has_indefinite_integral(),
+			_ => {
+				let (lower, upper) = self.bounds();
+				self.a.as_ref().unwrap().a.as_ref().unwrap().has_indefinite_integral() || lower.has_indefinite_integral() || upper.has_indefinite_integral()
+			}
+		}
+	}
+
+	// names of every variable appearing in a PARAM leaf of the tree
+	pub fn free_variables(&self) -> BTreeSet<String> {
+		let mut result: BTreeSet<String> = BTreeSet::new();
+		match self.children() {
+			0 => {
+				if self.op == EnumExpr::PARAM {
+					result.extend(self.x.variables.keys().cloned());
+				}
+			}
+			1 => result.extend(self.a.as_ref().unwrap().free_variables()),
+			3 => {
+				// the integration variable is bound
+				let x: String = self.integration_variable();
+				let (lower, upper) = self.bounds();
+				result.extend(self.a.as_ref().unwrap().a.as_ref().unwrap().free_variables().into_iter().filter(|s| *s != x));
+				result.extend(lower.free_variables());
+				result.extend(upper.free_variables());
+			}
+			_ => {
+				result.extend(self.a.as_ref().unwrap().free_variables());
+				result.extend(self.b.as_ref().unwrap().free_variables());
+			}
+		}
+		return result;
+	}
+
+	pub fn depends_on(&self, p: &str) -> bool {
+		match self.children() {
+			0 => self.op == EnumExpr::PARAM && self.x.variables.contains_key(p),
+			1 => self.a.as_ref().unwrap().depends_on(p),
+			3 => {
+				let integrand: &Expr = self.a.as_ref().unwrap().a.as_ref().unwrap();
+				let (lower, upper) = self.bounds();
+				(self.integration_variable() != p && integrand.depends_on(p)) || lower.depends_on(p) || upper.depends_on(p)
+			}
+			_ => self.a.as_ref().unwrap().depends_on(p) || self.b.as_ref().unwrap().depends_on(p),
+		}
+	}
+
+	// equality after canonicalisation, e.g. `2*x + y` and `y + x + x`
+	pub fn equivalent(&self, other: &Expr) -> bool {
+		let lhs: Expr = self.simplify();
+		let rhs: Expr = other.simplify();
+		if lhs == rhs {
+			return true;
+		}
+		let diff: Expr = lhs.Minus(rhs).simplify();
+		return diff.op == EnumExpr::CONSTANT && diff.x.coefficient == 0.0;
+	}
+
 	pub fn op(&self) -> EnumExpr {
 		self.op.clone()
 	}
@@ -0,0 +1,254 @@
+use std::collections::*;
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::poly::poly::Poly;
+
+// Symbolic integration.
+//
+// `integrate` is linear over sums, differences, negation and factors that do
+// not depend on the variable. Every remaining part is tried as: a polynomial
+// (through `Poly`), a power of the variable, a function of a linear argument,
+// a substitution u = g(x) where g' appears as a factor, and integration by
+// parts for a polynomial times sin or cos. A part without closed form stays an
+// unevaluated INTEGRAL node.
+
+fn constant(e: &Expr) -> Option<f64> {
+	if e.op == EnumExpr::CONSTANT || (e.op == EnumExpr::PARAM && e.x.variables.is_empty()) {
+		return Some(f64::from(e.x.coefficient.clone()));
+	}
+	return None;
+}
+
+fn is_zero(e: &Expr) -> bool {
+	match Poly::try_from(e) {
+		Ok(mut p) => p.zero(),
+		Err(_) => e.equivalent(&Expr::from(0.0)),
+	}
+}
+
+// d/dx of an argument that is linear in x, None otherwise
+fn slope(arg: &Expr, x: &str) -> Option<Expr> {
+	let d: Expr = arg.partial_wrt(x).simplify();
+	if d.depends_on(x) || is_zero(&d) {
+		return None;
+	}
+	return Some(d);
+}
+
+// x^n times factors free of x, for any real n except -1
+fn power_term(t: &Term, x: &str) -> Option<Expr> {
+	let n: f64 = f64::from(t.variables.get(x)?.clone());
+	if n == -1.0 {
+		return None;
+	}
+	let mut variables: Variables = t.variables.clone();
+	variables.insert(x.to_string(), Number::Decimal(n + 1.0));
+	return Some(Expr::from((Number::Decimal(f64::from(t.coefficient.clone()) / (n + 1.0)), variables)));
+}
+
+// antiderivative F of `outer` applied to u, as an expression in `u`
+fn outer_antiderivative(outer: &Expr, u: Expr) -> Option<Expr> {
+	match outer.op {
+		EnumExpr::SIN => Some(u.Cos().Negate()),
+		EnumExpr::COS => Some(u.Sin()),
+		// sqrt(u) -> 2/3 u^(3/2)
+		EnumExpr::SQRT => Some(Expr::from(2.0 / 3.0).Times(u.Pow(Expr::from(1.5)))),
+		EnumExpr::SQUARE => Some(u.Pow(Expr::from(3.0)).Div(Expr::from(3.0))),
+		EnumExpr::POW => {
+			let base: &Expr = outer.a.as_ref().unwrap();
+			let exponent: &Expr = outer.b.as_ref().unwrap();
+			match (constant(base), constant(exponent)) {
+				(_, Some(-1.0)) => None,
+				(_, Some(n)) => Some(u.Pow(Expr::from(n + 1.0)).Div(Expr::from(n + 1.0))),
+				// c^u -> c^u / ln(c)
+				(Some(c), None) if c > 0.0 && c != 1.0 => Some(Expr::from(c).Pow(u).Div(Expr::from(c.ln()))),
+				_ => None,
+			}
+		}
+		_ => None,
+	}
+}
+
+// the argument that `outer` is applied to: the single child, or the side of
+// a POW that is not constant
+fn inner(outer: &Expr) -> Option<Expr> {
+	match outer.op {
+		EnumExpr::SIN | EnumExpr::COS | EnumExpr::SQRT | EnumExpr::SQUARE => Some(outer.a.as_ref().unwrap().as_ref().clone()),
+		EnumExpr::POW => {
+			if constant(outer.b.as_ref().unwrap()).is_some() {
+				return Some(outer.a.as_ref().unwrap().as_ref().clone());
+			}
+			if constant(outer.a.as_ref().unwrap()).is_some() {
+				return Some(outer.b.as_ref().unwrap().as_ref().clone());
+			}
+			None
+		}
+		_ => None,
+	}
+}
+
+// f(a x + b) -> F(a x + b) / a
+fn linear_composite(e: &Expr, x: &str) -> Option<Expr> {
+	let u: Expr = inner(e)?;
+	let a: Expr = slope(&u, x)?;
+	return Some(outer_antiderivative(e, u)?.Div(a));
+}
+
+// c g'(x) f(g(x)) -> c F(g(x))
+fn substitution(factor: &Expr, outer: &Expr, x: &str) -> Option<Expr> {
+	let g: Expr = inner(outer)?;
+	let dg: Expr = g.partial_wrt(x).simplify();
+	// factor / g' is free of x exactly when factor' g' - factor g'' vanishes
+	let wronskian: Expr = factor.partial_wrt(x).Times(dg.clone()).Minus(factor.clone().Times(dg.partial_wrt(x)));
+	if is_zero(&dg) || !is_zero(&wronskian.simplify()) {
+		return None;
+	}
+	// read the constant ratio off a point where g' does not vanish
+	for x0 in [1.0, 2.0, 0.5, 3.0, -1.0] {
+		let point: BTreeMap<String, Expr> = BTreeMap::from([(x.to_string(), Expr::from(x0))]);
+		let denominator: Expr = dg.subs(&point)?.simplify();
+		if is_zero(&denominator) {
+			continue;
+		}
+		let ratio: Expr = factor.subs(&point)?.Div(denominator).simplify();
+		return Some(ratio.Times(outer_antiderivative(outer, g)?));
+	}
+	return None;
+}
+
+// p(x) sin(u) -> -p cos(u)/a + int p' cos(u)/a, and likewise for cos, with u = a x + b
+fn by_parts(p: &Expr, trig: &Expr, x: &str) -> Option<Expr> {
+	if trig.op != EnumExpr::SIN && trig.op != EnumExpr::COS {
+		return None;
+	}
+	let poly: Poly = Poly::try_from(p).ok()?;
+	let u: Expr = trig.a.as_ref().unwrap().as_ref().clone();
+	let a: Expr = slope(&u, x)?;
+	let dp: Expr = poly.derivative(x)?.e();
+	// a times the antiderivative of the trig factor is -cos(u) or sin(u)
+	let boundary: Expr = match trig.op {
+		EnumExpr::SIN => p.clone().Times(u.clone().Cos()).Div(a.clone()).Negate(),
+		_ => p.clone().Times(u.clone().Sin()).Div(a.clone()),
+	};
+	if is_zero(&dp) {
+		return Some(boundary);
+	}
+	match trig.op {
+		EnumExpr::SIN => Some(boundary.Plus(antiderivative(&dp.Times(u.Cos()), x)?.Div(a))),
+		_ => Some(boundary.Minus(antiderivative(&dp.Times(u.Sin()), x)?.Div(a))),
+	}
+}
+
+// closed form antiderivative of a part that is not a sum
+fn antiderivative(e: &Expr, x: &str) -> Option<Expr> {
+	if !e.depends_on(x) {
+		return Some(e.clone().Times(Expr::from(x)));
+	}
+	if let Some(p) = Poly::try_from(e).ok().and_then(|p| p.integrate(x)) {
+		return Some(p.e());
+	}
+	match e.op {
+		EnumExpr::PARAM => power_term(&e.x, x),
+		EnumExpr::PLUS | EnumExpr::MINUS | EnumExpr::NEGATE => {
+			let result: Expr = integrate(e, x);
+			if contains_integral(&result) {
+				return None;
+			}
+			Some(result)
+		}
+		EnumExpr::TIMES => {
+			let a: &Expr = e.a.as_ref().unwrap();
+			let b: &Expr = e.b.as_ref().unwrap();
+			if !a.depends_on(x) {
+				return Some(a.clone().Times(antiderivative(b, x)?));
+			}
+			if !b.depends_on(x) {
+				return Some(antiderivative(a, x)?.Times(b.clone()));
+			}
+			substitution(a, b, x)
+				.or_else(|| substitution(b, a, x))
+				.or_else(|| by_parts(a, b, x))
+				.or_else(|| by_parts(b, a, x))
+		}
+		EnumExpr::DIV => {
+			let b: &Expr = e.b.as_ref().unwrap();
+			if b.depends_on(x) {
+				return None;
+			}
+			Some(antiderivative(e.a.as_ref().unwrap(), x)?.Div(b.clone()))
+		}
+		_ => linear_composite(e, x),
+	}
+}
+
+fn integrate(e: &Expr, x: &str) -> Expr {
+	match e.op {
+		EnumExpr::PLUS => integrate(e.a.as_ref().unwrap(), x).Plus(integrate(e.b.as_ref().unwrap(), x)),
+		EnumExpr::MINUS => integrate(e.a.as_ref().unwrap(), x).Minus(integrate(e.b.as_ref().unwrap(), x)),
+		EnumExpr::NEGATE => integrate(e.a.as_ref().unwrap(), x).Negate(),
+		_ => match antiderivative(e, x) {
+			Some(result) => result,
+			None => e.clone().Integral(x),
+		},
+	}
+}
+
+fn contains_integral(e: &Expr) -> bool {
+	match e.children() {
+		0 => false,
+		_ if e.op == EnumExpr::INTEGRAL || e.op == EnumExpr::DEFINITE => true,
+		1 => contains_integral(e.a.as_ref().unwrap()),
+		_ => contains_integral(e.a.as_ref().unwrap()) || contains_integral(e.b.as_ref().unwrap()),
+	}
+}
+
+// adaptive Simpson rule, used to evaluate DEFINITE nodes
+pub(crate) fn quadrature(f: &Expr, x: &str, lower: f64, upper: f64, values: &Variables) -> f64 {
+	let eval = |t: f64| -> f64 {
+		let mut point: Variables = values.clone();
+		point.insert(x.to_string(), Number::Decimal(t));
+		return f64::from(f.Eval(&point));
+	};
+	fn simpson(fa: f64, fm: f64, fb: f64, a: f64, b: f64) -> f64 {
+		return (b - a) / 6.0 * (fa + 4.0 * fm + fb);
+	}
+	// `f` holds the values at a, (a + b)/2 and b
+	fn adapt(eval: &dyn Fn(f64) -> f64, (a, b): (f64, f64), f: (f64, f64, f64), whole: f64, tol: f64, depth: usize) -> f64 {
+		let (fa, fm, fb) = f;
+		let m: f64 = (a + b) / 2.0;
+		let (flm, frm) = (eval((a + m) / 2.0), eval((m + b) / 2.0));
+		let left: f64 = simpson(fa, flm, fm, a, m);
+		let right: f64 = simpson(fm, frm, fb, m, b);
+		if depth == 0 || (left + right - whole).abs() <= 15.0 * tol {
+			return left + right + (left + right - whole) / 15.0;
+		}
+		return adapt(eval, (a, m), (fa, flm, fm), left, tol / 2.0, depth - 1) + adapt(eval, (m, b), (fm, frm, fb), right, tol / 2.0, depth - 1);
+	}
+	let f: (f64, f64, f64) = (eval(lower), eval((lower + upper) / 2.0), eval(upper));
+	let whole: f64 = simpson(f.0, f.1, f.2, lower, upper);
+	return adapt(&eval, (lower, upper), f, whole, 1e-10, 40);
+}
+
+impl Expr {
+	// indefinite integral without integration constant, parts without a closed
+	// form are left as INTEGRAL nodes
+	pub fn integrate(&self, x: &str) -> Expr {
+		return integrate(self, x).simplify();
+	}
+
+	// F(upper) - F(lower) when a closed form exists, a DEFINITE node otherwise
+	pub fn integrate_between(&self, x: &str, lower: Expr, upper: Expr) -> Expr {
+		let antiderivative: Expr = integrate(self, x);
+		if contains_integral(&antiderivative) {
+			return self.clone().Definite(x, lower, upper);
+		}
+		let at = |bound: &Expr| -> Option<Expr> {
+			return antiderivative.subs(&BTreeMap::from([(x.to_string(), bound.clone())]));
+		};
+		match (at(&upper), at(&lower)) {
+			(Some(upper), Some(lower)) => return upper.Minus(lower).simplify(),
+			_ => return self.clone().Definite(x, lower, upper),
+		}
+	}
+}
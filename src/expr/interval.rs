@@ -0,0 +1,386 @@
+use std::collections::*;
+use std::f64::consts::PI;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::expr::expr::*;
+
+// Interval arithmetic.
+//
+// Every operation returns an interval that contains all results for all
+// points of its operands. Bounds that floating point had to round are moved
+// one ulp outwards, so rounding can only widen the enclosure. Points outside the
+// domain of SQRT, ASIN, ACOS, LOG and real powers are dropped, and an operation
+// with no valid point at all gives the empty interval. Division by an
+// interval containing zero gives the hull of the possible results, which can
+// be a half line or the whole line.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+	pub lo: f64,
+	pub hi: f64,
+}
+
+// a result of +, * or / on two bounds together with whether it is exact;
+// only inexact bounds are moved outwards, so exact zeros stay zeros
+type Bound = (f64, bool);
+
+fn sum(a: f64, b: f64) -> Bound {
+	let s: f64 = a + b;
+	if !a.is_finite() || !b.is_finite() {
+		return (s, true);
+	}
+	// two-sum error term
+	let t: f64 = s - a;
+	return (s, s.is_finite() && (a - (s - t)) + (b - t) == 0.0);
+}
+
+// 0 * inf counts as 0
+fn product(a: f64, b: f64) -> Bound {
+	if a == 0.0 || b == 0.0 {
+		return (0.0, true);
+	}
+	let p: f64 = a * b;
+	if !a.is_finite() || !b.is_finite() {
+		return (p, true);
+	}
+	return (p, p.is_finite() && a.mul_add(b, -p) == 0.0);
+}
+
+fn quotient(a: f64, b: f64) -> Bound {
+	let q: f64 = a / b;
+	if !a.is_finite() || !b.is_finite() {
+		return (q, true);
+	}
+	return (q, q.is_finite() && q.mul_add(b, -a) == 0.0);
+}
+
+// least and greatest candidate, a NaN from inf / inf is skipped as the other
+// candidates already bound the result
+fn hull(candidates: &[Bound]) -> Interval {
+	let mut lo: Bound = (f64::INFINITY, true);
+	let mut hi: Bound = (f64::NEG_INFINITY, true);
+	for (v, exact) in candidates.iter().filter(|(v, _)| !v.is_nan()) {
+		if *v < lo.0 || (*v == lo.0 && !exact) {
+			lo = (*v, *exact);
+		}
+		if *v > hi.0 || (*v == hi.0 && !exact) {
+			hi = (*v, *exact);
+		}
+	}
+	if lo.0 > hi.0 {
+		return Interval::empty();
+	}
+	return Interval {
+		lo: if lo.1 { lo.0 } else { lo.0.next_down() },
+		hi: if hi.1 { hi.0 } else { hi.0.next_up() },
+	};
+}
+
+impl Interval {
+	pub fn new(lo: f64, hi: f64) -> Interval {
+		assert!(lo <= hi, "interval [{}, {}] has lower bound above upper bound", lo, hi);
+		return Interval { lo, hi };
+	}
+
+	pub fn point(x: f64) -> Interval {
+		return Interval { lo: x, hi: x };
+	}
+
+	pub fn empty() -> Interval {
+		return Interval { lo: f64::INFINITY, hi: f64::NEG_INFINITY };
+	}
+
+	pub fn entire() -> Interval {
+		return Interval { lo: f64::NEG_INFINITY, hi: f64::INFINITY };
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.lo > self.hi || self.lo.is_nan() || self.hi.is_nan();
+	}
+
+	pub fn contains(&self, x: f64) -> bool {
+		return self.lo <= x && x <= self.hi;
+	}
+
+	pub fn width(&self) -> f64 {
+		return self.hi - self.lo;
+	}
+
+	// smallest interval containing both
+	pub fn hull(&self, other: &Interval) -> Interval {
+		if self.is_empty() {
+			return *other;
+		}
+		if other.is_empty() {
+			return *self;
+		}
+		return Interval { lo: self.lo.min(other.lo), hi: self.hi.max(other.hi) };
+	}
+
+	// the part inside [lo, hi]
+	fn clip(&self, lo: f64, hi: f64) -> Interval {
+		let result: Interval = Interval { lo: self.lo.max(lo), hi: self.hi.min(hi) };
+		if result.is_empty() {
+			return Interval::empty();
+		}
+		return result;
+	}
+
+	// one ulp outwards, the bounds are computed with rounding to nearest
+	fn outward(lo: f64, hi: f64) -> Interval {
+		if lo > hi || lo.is_nan() || hi.is_nan() {
+			return Interval::empty();
+		}
+		return Interval { lo: lo.next_down(), hi: hi.next_up() };
+	}
+
+	// image of a monotone function, `increasing` tells its direction
+	fn monotone(&self, f: impl Fn(f64) -> f64, increasing: bool) -> Interval {
+		if self.is_empty() {
+			return Interval::empty();
+		}
+		let (a, b) = (f(self.lo), f(self.hi));
+		if increasing {
+			return Interval::outward(a, b);
+		}
+		return Interval::outward(b, a);
+	}
+
+	pub fn sqrt(&self) -> Interval {
+		let result: Interval = self.clip(0.0, f64::INFINITY).monotone(f64::sqrt, true);
+		return result.clip(0.0, f64::INFINITY);
+	}
+
+	pub fn square(&self) -> Interval {
+		if self.is_empty() {
+			return Interval::empty();
+		}
+		let (a, b) = (self.lo * self.lo, self.hi * self.hi);
+		if self.contains(0.0) {
+			return Interval::outward(0.0, a.max(b)).clip(0.0, f64::INFINITY);
+		}
+		return Interval::outward(a.min(b), a.max(b));
+	}
+
+	// whether [lo, hi] may contain offset + 2 k pi for an integer k, erring
+	// towards yes since an extra extremum only widens the result
+	fn reaches(&self, offset: f64) -> bool {
+		let k: f64 = ((self.lo - offset) / (2.0 * PI)).ceil() - 1.0;
+		return [k, k + 1.0].iter().any(|k| {
+			let x: f64 = offset + 2.0 * PI * k;
+			let slack: f64 = 1e-12 * x.abs().max(1.0);
+			return self.lo - slack <= x && x <= self.hi + slack;
+		});
+	}
+
+	// image of sin or cos given where the maximum and the minimum lie
+	fn periodic(&self, f: fn(f64) -> f64, maximum: f64, minimum: f64) -> Interval {
+		if self.is_empty() {
+			return Interval::empty();
+		}
+		if self.width() >= 2.0 * PI || self.width().is_nan() {
+			return Interval::new(-1.0, 1.0);
+		}
+		let (a, b) = (f(self.lo), f(self.hi));
+		let mut result: Interval = Interval::outward(a.min(b), a.max(b));
+		if self.reaches(maximum) {
+			result.hi = 1.0;
+		}
+		if self.reaches(minimum) {
+			result.lo = -1.0;
+		}
+		return result.clip(-1.0, 1.0);
+	}
+
+	pub fn sin(&self) -> Interval {
+		return self.periodic(f64::sin, PI / 2.0, -PI / 2.0);
+	}
+
+	pub fn cos(&self) -> Interval {
+		return self.periodic(f64::cos, 0.0, PI);
+	}
+
+	pub fn asin(&self) -> Interval {
+		let result: Interval = self.clip(-1.0, 1.0).monotone(f64::asin, true);
+		return result.clip(-PI / 2.0, PI / 2.0);
+	}
+
+	pub fn acos(&self) -> Interval {
+		let result: Interval = self.clip(-1.0, 1.0).monotone(f64::acos, false);
+		return result.clip(0.0, PI);
+	}
+
+	fn exp(&self) -> Interval {
+		return self.monotone(f64::exp, true).clip(0.0, f64::INFINITY);
+	}
+
+	pub fn ln(&self) -> Interval {
+		return self.clip(0.0, f64::INFINITY).monotone(f64::ln, true);
+	}
+
+	// self^n for a constant n
+	pub fn powf(&self, n: f64) -> Interval {
+		if self.is_empty() {
+			return Interval::empty();
+		}
+		if n == 0.0 {
+			return Interval::point(1.0);
+		}
+		if n == 1.0 {
+			return *self;
+		}
+		if n.fract() == 0.0 {
+			if n < 0.0 {
+				return Interval::point(1.0) / self.powf(-n);
+			}
+			if n % 2.0 == 0.0 {
+				let magnitude: f64 = self.lo.abs().max(self.hi.abs());
+				let least: f64 = if self.contains(0.0) { 0.0 } else { self.lo.abs().min(self.hi.abs()) };
+				return Interval::outward(least.powf(n), magnitude.powf(n)).clip(0.0, f64::INFINITY);
+			}
+			return self.monotone(|x| x.powf(n), true);
+		}
+		// a fractional power is only real for x >= 0
+		return self.clip(0.0, f64::INFINITY).monotone(|x| x.powf(n), n > 0.0).clip(0.0, f64::INFINITY);
+	}
+
+	pub fn pow(&self, exponent: &Interval) -> Interval {
+		if exponent.lo == exponent.hi {
+			return self.powf(exponent.lo);
+		}
+		// x^y = exp(y ln x) for x >= 0
+		return (*exponent * self.ln()).exp();
+	}
+}
+
+impl Display for Interval {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		if self.is_empty() {
+			return write!(f, "[]");
+		}
+		return write!(f, "[{}, {}]", self.lo, self.hi);
+	}
+}
+
+impl Add for Interval {
+	type Output = Interval;
+	fn add(self, other: Interval) -> Interval {
+		if self.is_empty() || other.is_empty() {
+			return Interval::empty();
+		}
+		// inf - inf only comes from unbounded operands, whose sum is unbounded
+		let (lo, hi) = (sum(self.lo, other.lo), sum(self.hi, other.hi));
+		return hull(&[
+			if lo.0.is_nan() { (f64::NEG_INFINITY, true) } else { lo },
+			if hi.0.is_nan() { (f64::INFINITY, true) } else { hi },
+		]);
+	}
+}
+
+impl Sub for Interval {
+	type Output = Interval;
+	fn sub(self, other: Interval) -> Interval {
+		return self + -other;
+	}
+}
+
+impl Mul for Interval {
+	type Output = Interval;
+	fn mul(self, other: Interval) -> Interval {
+		if self.is_empty() || other.is_empty() {
+			return Interval::empty();
+		}
+		return hull(&[
+			product(self.lo, other.lo), product(self.lo, other.hi),
+			product(self.hi, other.lo), product(self.hi, other.hi),
+		]);
+	}
+}
+
+impl Div for Interval {
+	type Output = Interval;
+	fn div(self, other: Interval) -> Interval {
+		if self.is_empty() || other.is_empty() || (other.lo == 0.0 && other.hi == 0.0) {
+			return Interval::empty();
+		}
+		if !other.contains(0.0) {
+			return hull(&[
+				quotient(self.lo, other.lo), quotient(self.lo, other.hi),
+				quotient(self.hi, other.lo), quotient(self.hi, other.hi),
+			]);
+		}
+		if self.contains(0.0) || (other.lo < 0.0 && other.hi > 0.0) {
+			return Interval::entire();
+		}
+		// the divisor touches zero at one end only, the result is a half line
+		let (lo, hi) = match (self.hi < 0.0, other.lo == 0.0) {
+			(true, true) => ((f64::NEG_INFINITY, true), quotient(self.hi, other.hi)),
+			(true, false) => (quotient(self.hi, other.lo), (f64::INFINITY, true)),
+			(false, true) => (quotient(self.lo, other.hi), (f64::INFINITY, true)),
+			(false, false) => ((f64::NEG_INFINITY, true), quotient(self.lo, other.lo)),
+		};
+		return hull(&[lo, hi]);
+	}
+}
+
+impl Neg for Interval {
+	type Output = Interval;
+	fn neg(self) -> Interval {
+		if self.is_empty() {
+			return self;
+		}
+		return Interval { lo: -self.hi, hi: -self.lo };
+	}
+}
+
+fn eval_interval(e: &Expr, values: &BTreeMap<String, Interval>) -> Interval {
+	match e.op {
+		EnumExpr::CONSTANT => Interval::point(f64::from(e.x.coefficient.clone())),
+		EnumExpr::PARAM => {
+			let mut result: Interval = Interval::point(f64::from(e.x.coefficient.clone()));
+			for (key, exp) in e.x.variables.iter() {
+				let value: &Interval = match values.get(key) {
+					Some(value) => value,
+					None => panic!("no interval for variable {}", key),
+				};
+				result = result * value.powf(f64::from(exp.clone()));
+			}
+			result
+		}
+		EnumExpr::PLUS => eval_interval(e.a.as_ref().unwrap(), values) + eval_interval(e.b.as_ref().unwrap(), values),
+		EnumExpr::MINUS => eval_interval(e.a.as_ref().unwrap(), values) - eval_interval(e.b.as_ref().unwrap(), values),
+		EnumExpr::TIMES => eval_interval(e.a.as_ref().unwrap(), values) * eval_interval(e.b.as_ref().unwrap(), values),
+		EnumExpr::DIV => eval_interval(e.a.as_ref().unwrap(), values) / eval_interval(e.b.as_ref().unwrap(), values),
+		EnumExpr::POW => eval_interval(e.a.as_ref().unwrap(), values).pow(&eval_interval(e.b.as_ref().unwrap(), values)),
+		EnumExpr::NEGATE => -eval_interval(e.a.as_ref().unwrap(), values),
+		EnumExpr::SQRT => eval_interval(e.a.as_ref().unwrap(), values).sqrt(),
+		EnumExpr::SQUARE => eval_interval(e.a.as_ref().unwrap(), values).square(),
+		EnumExpr::SIN => eval_interval(e.a.as_ref().unwrap(), values).sin(),
+		EnumExpr::COS => eval_interval(e.a.as_ref().unwrap(), values).cos(),
+		EnumExpr::ASIN => eval_interval(e.a.as_ref().unwrap(), values).asin(),
+		EnumExpr::ACOS => eval_interval(e.a.as_ref().unwrap(), values).acos(),
+		EnumExpr::LOG => eval_interval(e.a.as_ref().unwrap(), values).ln(),
+		// an indefinite integral has no value
+		EnumExpr::INTEGRAL => Interval::empty(),
+		EnumExpr::DEFINITE => {
+			// the integral is (upper - lower) times the mean of the integrand,
+			// and the mean lies in the range over everything between the bounds
+			let (lower, upper) = e.bounds();
+			let upper: Interval = eval_interval(upper, values);
+			let lower: Interval = eval_interval(lower, values);
+			let mut inside: BTreeMap<String, Interval> = values.clone();
+			inside.insert(e.integration_variable(), upper.hull(&lower));
+			let integrand: &Expr = e.a.as_ref().unwrap().a.as_ref().unwrap();
+			(upper - lower) * eval_interval(integrand, &inside)
+		}
+		_ => panic!("Unknown operation"),
+	}
+}
+
+impl Expr {
+	// an interval containing the value at every point of the given intervals
+	pub fn eval_interval(&self, values: &BTreeMap<String, Interval>) -> Interval {
+		return eval_interval(self, values);
+	}
+}
@@ -0,0 +1,371 @@
+use std::collections::*;
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::expr::series::Series;
+
+// Limits of expressions in one variable.
+//
+// A one-sided limit at a finite point is read off the Laurent series when it
+// exists: the constant term, or an infinity whose sign follows the leading
+// coefficient and, from the left, the parity of the pole. Where no series
+// exists (branch points, essential singularities) the limit is taken node by
+// node, with L'Hôpital's rule for 0/0 and ∞/∞ and rewrites of 0·∞ and ∞ - ∞
+// into quotients. Limits at ±∞ substitute x = ±1/t and approach t = 0 from
+// the right.
+//
+// Zero tests are exact, except that a sum cancelling to within `EPS` of its
+// largest operand counts as zero. Variables other than `var` are rejected
+// rather than given a value, and so are integrals over `var` and limits the
+// rewrites cannot settle within `MAX_DEPTH` steps.
+
+const MAX_DEPTH: usize = 8;
+const EPS: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitPoint {
+	Finite(f64),
+	PosInfinity,
+	NegInfinity,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+	Both,
+	Left,  // from below
+	Right, // from above
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+	Finite(f64),
+	PosInfinity,
+	NegInfinity,
+	DoesNotExist,
+}
+
+// a bounded oscillation such as sin(1/x) at 0 has no limit, but it still
+// vanishes when divided by something unbounded
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+	Finite(f64),
+	PosInf,
+	NegInf,
+	Bounded,
+	Undefined,
+}
+
+impl Value {
+	fn from_f64(v: f64) -> Value {
+		match v {
+			v if v.is_nan() => Value::Undefined,
+			f64::INFINITY => Value::PosInf,
+			f64::NEG_INFINITY => Value::NegInf,
+			_ => Value::Finite(v),
+		}
+	}
+
+	fn infinite(sign: f64) -> Value {
+		if sign < 0.0 {
+			return Value::NegInf;
+		}
+		return Value::PosInf;
+	}
+
+	fn is_infinite(&self) -> bool {
+		return matches!(self, Value::PosInf | Value::NegInf);
+	}
+
+	fn is_zero(&self) -> bool {
+		return matches!(self, Value::Finite(v) if *v == 0.0);
+	}
+
+	// sign of a nonzero value, 0 when it has none
+	fn sign(&self) -> f64 {
+		match self {
+			Value::Finite(v) => v.signum(),
+			Value::PosInf => 1.0,
+			Value::NegInf => -1.0,
+			_ => 0.0,
+		}
+	}
+
+	fn negate(self) -> Value {
+		match self {
+			Value::Finite(v) => Value::Finite(-v),
+			Value::PosInf => Value::NegInf,
+			Value::NegInf => Value::PosInf,
+			other => other,
+		}
+	}
+}
+
+struct Limits<'a> {
+	var: &'a str,
+	point: f64,
+	side: f64, // 1 from the right, -1 from the left
+}
+
+impl Limits<'_> {
+	// sign of `e` just beside the point, for c/0 forms
+	fn approach_sign(&self, e: &Expr) -> f64 {
+		if let Ok(series) = e.series(self.var, self.point, 0)
+			&& let Some(v) = series.valuation() {
+			let parity: f64 = if self.side < 0.0 && v % 2 != 0 { -1.0 } else { 1.0 };
+			return series.coefficient(v).signum() * parity;
+		}
+		let near: f64 = self.point + self.side * 1e-9 * self.point.abs().max(1.0);
+		return f64::from(e.Eval(&Variables::from([(self.var.to_string(), Number::Decimal(near))]))).signum();
+	}
+
+	fn of_series(&self, series: &Series) -> Value {
+		match series.valuation() {
+			None => Value::Finite(0.0),
+			Some(v) if v >= 0 => Value::Finite(series.coefficient(0)),
+			Some(v) => {
+				let parity: f64 = if self.side < 0.0 && v % 2 != 0 { -1.0 } else { 1.0 };
+				Value::infinite(series.coefficient(v) * parity)
+			}
+		}
+	}
+
+	fn leaf(&self, x: &Term) -> Value {
+		let c: f64 = f64::from(x.coefficient.clone());
+		let p: f64 = match x.variables.get(self.var) {
+			Some(p) => f64::from(p.clone()),
+			None => 0.0,
+		};
+		if self.point != 0.0 || p == 0.0 {
+			return Value::from_f64(c * self.point.powf(p));
+		}
+		// x^p at 0: fractional powers only exist from the right
+		if self.side < 0.0 && p.fract() != 0.0 {
+			return Value::Undefined;
+		}
+		if p > 0.0 {
+			return Value::Finite(0.0);
+		}
+		let parity: f64 = if self.side < 0.0 && p % 2.0 != 0.0 { -1.0 } else { 1.0 };
+		return Value::infinite(c * parity);
+	}
+
+	fn add(&self, a: &Expr, b: &Expr, sign: f64, depth: usize) -> Result<Value, String> {
+		let la: Value = self.value(a, depth)?;
+		let lb: Value = if sign < 0.0 { self.value(b, depth)?.negate() } else { self.value(b, depth)? };
+		let value: Value = match (la, lb) {
+			(Value::Undefined, _) | (_, Value::Undefined) => Value::Undefined,
+			(Value::Finite(x), Value::Finite(y)) if (x + y).abs() <= EPS * x.abs().max(y.abs()) => Value::Finite(0.0),
+			(Value::Finite(x), Value::Finite(y)) => Value::Finite(x + y),
+			(Value::PosInf, Value::NegInf) | (Value::NegInf, Value::PosInf) => {
+				// a + b = (1/b + 1/a) / (1/(a b))
+				let b: Expr = if sign < 0.0 { b.clone().Negate() } else { b.clone() };
+				let one: Expr = Expr::from(1.0);
+				let numerator: Expr = one.clone().Div(b.clone()).Plus(one.clone().Div(a.clone()));
+				return self.value(&numerator.Div(one.Div(a.clone().Times(b))), depth + 1);
+			}
+			(x, _) if x.is_infinite() => x,
+			(_, y) if y.is_infinite() => y,
+			_ => Value::Bounded,
+		};
+		return Ok(value);
+	}
+
+	fn mul(&self, a: &Expr, b: &Expr, depth: usize) -> Result<Value, String> {
+		let (la, lb) = (self.value(a, depth)?, self.value(b, depth)?);
+		let value: Value = match (la, lb) {
+			(Value::Undefined, _) | (_, Value::Undefined) => Value::Undefined,
+			(Value::Finite(x), Value::Finite(y)) => Value::Finite(x * y),
+			(x, y) if (x.is_zero() && y.is_infinite()) || (x.is_infinite() && y.is_zero()) => {
+				// a b = a / (1/b), or b / (1/a) when L'Hôpital makes no progress on the first
+				let one: Expr = Expr::from(1.0);
+				return self.value(&a.clone().Div(one.clone().Div(b.clone())), depth + 1)
+					.or_else(|_| self.value(&b.clone().Div(one.Div(a.clone())), depth + 1));
+			}
+			(x, y) if x.is_infinite() || y.is_infinite() => {
+				if x == Value::Bounded || y == Value::Bounded {
+					return Ok(Value::Undefined);
+				}
+				Value::infinite(x.sign() * y.sign())
+			}
+			(x, y) if x.is_zero() || y.is_zero() => Value::Finite(0.0),
+			_ => Value::Bounded,
+		};
+		return Ok(value);
+	}
+
+	fn div(&self, a: &Expr, b: &Expr, depth: usize) -> Result<Value, String> {
+		let (la, lb) = (self.value(a, depth)?, self.value(b, depth)?);
+		let value: Value = match (la, lb) {
+			(Value::Undefined, _) | (_, Value::Undefined) => Value::Undefined,
+			(x, y) if (x.is_zero() && y.is_zero()) || (x.is_infinite() && y.is_infinite()) => {
+				// L'Hôpital
+				let da: Expr = a.partial_wrt(self.var).simplify();
+				let db: Expr = b.partial_wrt(self.var).simplify();
+				return self.value(&da.Div(db), depth + 1);
+			}
+			(x, y) if y.is_zero() => {
+				if x == Value::Bounded {
+					return Ok(Value::Undefined);
+				}
+				Value::infinite(x.sign() * self.approach_sign(b))
+			}
+			(Value::Finite(x), Value::Finite(y)) => Value::Finite(x / y),
+			(_, y) if y.is_infinite() => Value::Finite(0.0),
+			(x, Value::Finite(y)) if x.is_infinite() => Value::infinite(x.sign() * y.signum()),
+			_ => Value::Bounded,
+		};
+		return Ok(value);
+	}
+
+	fn unary(&self, op: &EnumExpr, inner: Value) -> Value {
+		match (op, inner) {
+			(_, Value::Undefined) => Value::Undefined,
+			(EnumExpr::SIN | EnumExpr::COS, Value::PosInf | Value::NegInf | Value::Bounded) => Value::Bounded,
+			(EnumExpr::SQUARE, Value::PosInf | Value::NegInf) => Value::PosInf,
+			(EnumExpr::SQRT | EnumExpr::LOG, Value::PosInf) => Value::PosInf,
+			(_, Value::Finite(v)) => {
+				let result: f64 = match op {
+					EnumExpr::SQRT => v.max(0.0).sqrt(),
+					EnumExpr::SQUARE => v * v,
+					EnumExpr::SIN => v.sin(),
+					EnumExpr::COS => v.cos(),
+					EnumExpr::ASIN => v.asin(),
+					EnumExpr::LOG => v.ln(),
+					_ => v.acos(),
+				};
+				if *op == EnumExpr::SQRT && v < 0.0 {
+					return Value::Undefined;
+				}
+				Value::from_f64(result)
+			}
+			_ => Value::Undefined,
+		}
+	}
+
+	fn pow(&self, base: &Expr, exponent: &Expr, depth: usize) -> Result<Value, String> {
+		if exponent.depends_on(self.var) && base.depends_on(self.var) {
+			// a^b = exp(b log(a)), for a base that stays positive
+			let value: Value = match self.value(base, depth)? {
+				Value::Finite(v) if v < 0.0 => Value::Undefined,
+				Value::Finite(v) if v == 0.0 && self.approach_sign(base) < 0.0 => Value::Undefined,
+				Value::Finite(_) | Value::PosInf => match self.value(&exponent.clone().Times(base.clone().Log()), depth + 1)? {
+					Value::Finite(l) => Value::Finite(l.exp()),
+					Value::PosInf => Value::PosInf,
+					Value::NegInf => Value::Finite(0.0),
+					_ => Value::Undefined,
+				},
+				_ => Value::Undefined,
+			};
+			return Ok(value);
+		}
+		if !exponent.depends_on(self.var) {
+			let p: f64 = f64::from(exponent.Eval(&Variables::new()));
+			let value: Value = match self.value(base, depth)? {
+				Value::Finite(b) if b == 0.0 && p < 0.0 => {
+					let sign: f64 = if p.fract() == 0.0 && p % 2.0 != 0.0 { self.approach_sign(base) } else { 1.0 };
+					Value::infinite(sign)
+				}
+				Value::Finite(b) => Value::from_f64(b.powf(p)),
+				Value::PosInf if p > 0.0 => Value::PosInf,
+				Value::PosInf | Value::NegInf if p < 0.0 => Value::Finite(0.0),
+				Value::NegInf if p.fract() == 0.0 => Value::infinite(if p % 2.0 == 0.0 { 1.0 } else { -1.0 }),
+				_ => Value::Undefined,
+			};
+			return Ok(value);
+		}
+		let c: f64 = f64::from(base.Eval(&Variables::new()));
+		let value: Value = match self.value(exponent, depth)? {
+			Value::Finite(p) => Value::from_f64(c.powf(p)),
+			Value::PosInf if c > 1.0 => Value::PosInf,
+			Value::PosInf if c > 0.0 && c < 1.0 => Value::Finite(0.0),
+			Value::NegInf if c > 1.0 => Value::Finite(0.0),
+			Value::NegInf if c > 0.0 && c < 1.0 => Value::PosInf,
+			_ => Value::Undefined,
+		};
+		return Ok(value);
+	}
+
+	fn value(&self, e: &Expr, depth: usize) -> Result<Value, String> {
+		if depth > MAX_DEPTH {
+			return Err(format!("limit of {} could not be resolved", e));
+		}
+		if !e.depends_on(self.var) {
+			return Ok(Value::from_f64(f64::from(e.Eval(&Variables::new()))));
+		}
+		if let Ok(series) = e.series(self.var, self.point, 0) {
+			return Ok(self.of_series(&series));
+		}
+		let value: Value = match e.op {
+			EnumExpr::PARAM => self.leaf(&e.x),
+			EnumExpr::PLUS => self.add(e.a.as_ref().unwrap(), e.b.as_ref().unwrap(), 1.0, depth)?,
+			EnumExpr::MINUS => self.add(e.a.as_ref().unwrap(), e.b.as_ref().unwrap(), -1.0, depth)?,
+			EnumExpr::NEGATE => self.value(e.a.as_ref().unwrap(), depth)?.negate(),
+			EnumExpr::TIMES => self.mul(e.a.as_ref().unwrap(), e.b.as_ref().unwrap(), depth)?,
+			EnumExpr::DIV => self.div(e.a.as_ref().unwrap(), e.b.as_ref().unwrap(), depth)?,
+			EnumExpr::POW => self.pow(e.a.as_ref().unwrap(), e.b.as_ref().unwrap(), depth)?,
+			EnumExpr::INTEGRAL | EnumExpr::DEFINITE => return Err(format!("limit of the integral {} is not supported", e)),
+			EnumExpr::SQRT => {
+				let inner: &Expr = e.a.as_ref().unwrap();
+				let v: Value = self.value(inner, depth)?;
+				// sqrt of an argument that reaches 0 from below is not real
+				if v.is_zero() && self.approach_sign(inner) < 0.0 {
+					return Ok(Value::Undefined);
+				}
+				self.unary(&e.op, v)
+			}
+			_ => self.unary(&e.op, self.value(e.a.as_ref().unwrap(), depth)?),
+		};
+		return Ok(value);
+	}
+}
+
+fn same(a: Value, b: Value) -> bool {
+	match (a, b) {
+		(Value::Finite(x), Value::Finite(y)) => (x - y).abs() <= EPS * x.abs().max(y.abs()),
+		(Value::PosInf, Value::PosInf) | (Value::NegInf, Value::NegInf) => true,
+		_ => false,
+	}
+}
+
+impl Expr {
+	// limit as `var` approaches `point`, the direction only matters for finite
+	// points; an error when the expression has other free variables or the
+	// limit cannot be resolved
+	pub fn limit(&self, var: &str, point: LimitPoint, direction: Direction) -> Result<Limit, String> {
+		if let Some(other) = self.free_variables().into_iter().find(|s| s != var) {
+			return Err(format!("limit in {} with the free variable {}", var, other));
+		}
+		let value: Value = match point {
+			LimitPoint::Finite(a) => {
+				let one_sided = |side: f64| -> Result<Value, String> {
+					return Limits { var, point: a, side }.value(self, 0);
+				};
+				match direction {
+					Direction::Left => one_sided(-1.0)?,
+					Direction::Right => one_sided(1.0)?,
+					Direction::Both => {
+						let (left, right) = (one_sided(-1.0)?, one_sided(1.0)?);
+						if same(left, right) { right } else { Value::Undefined }
+					}
+				}
+			}
+			LimitPoint::PosInfinity | LimitPoint::NegInfinity => {
+				// x = ±1/t with t -> 0+
+				let sign: f64 = if point == LimitPoint::PosInfinity { 1.0 } else { -1.0 };
+				let limits: Limits = Limits { var, point: 0.0, side: 1.0 };
+				let inverse: Expr = Expr::from(sign).Div(Expr::from(var));
+				match self.subs(&BTreeMap::from([(var.to_string(), inverse)])) {
+					Some(e) => limits.value(&e, 0)?,
+					None => Value::Undefined,
+				}
+			}
+		};
+		let limit: Limit = match value {
+			Value::Finite(v) => Limit::Finite(v),
+			Value::PosInf => Limit::PosInfinity,
+			Value::NegInf => Limit::NegInfinity,
+			_ => Limit::DoesNotExist,
+		};
+		return Ok(limit);
+	}
+}
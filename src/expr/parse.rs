@@ -0,0 +1,336 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::expr::expr::*;
+
+// Recursive descent parser for infix expressions such as `3*x^2*y - sin(t)/2`.
+//
+// Grammar, lowest precedence first:
+//   sum     := product (('+' | '-') product)*
+//   product := unary (('*' | '/') unary)*
+//   unary   := ('-' | '+') unary | power
+//   power   := primary ('^' unary)?        right associative, -x^2 = -(x^2)
+//   primary := number | name | name '(' sum ')' | '(' sum ')' | integral
+//   integral := 'integrate' '(' sum ',' name (',' sum ',' sum)? ')'
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+	pub column: usize, // 1-based column of the offending character
+	pub message: String,
+}
+
+impl ParseError {
+	fn new(column: usize, message: &str) -> Self {
+		ParseError { column, message: message.to_string() }
+	}
+}
+
+impl Display for ParseError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "column {}: {}", self.column, self.message)
+	}
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+	Num(f64),
+	Name(String),
+	Op(char),
+	LParen,
+	RParen,
+	Comma,
+	End,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+	let chars: Vec<char> = input.chars().collect();
+	let mut tokens: Vec<(Token, usize)> = Vec::new();
+	let mut i: usize = 0;
+
+	while i < chars.len() {
+		let c = chars[i];
+		let column = i + 1;
+		if c.is_whitespace() {
+			i += 1;
+		}
+		else if c.is_ascii_digit() || c == '.' {
+			let start = i;
+			while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+				i += 1;
+			}
+			// scientific notation, only when an exponent actually follows the `e`
+			if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+				let mut j = i + 1;
+				if j < chars.len() && (chars[j] == '+' || chars[j] == '-') {
+					j += 1;
+				}
+				if j < chars.len() && chars[j].is_ascii_digit() {
+					i = j;
+					while i < chars.len() && chars[i].is_ascii_digit() {
+						i += 1;
+					}
+				}
+			}
+			let text: String = chars[start..i].iter().collect();
+			match text.parse::<f64>() {
+				Ok(val) => tokens.push((Token::Num(val), column)),
+				Err(_) => return Err(ParseError::new(column, &format!("invalid number `{}`", text))),
+			}
+		}
+		else if c.is_alphabetic() || c == '_' {
+			let start = i;
+			while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+				i += 1;
+			}
+			tokens.push((Token::Name(chars[start..i].iter().collect()), column));
+		}
+		else if "+-*/^".contains(c) {
+			tokens.push((Token::Op(c), column));
+			i += 1;
+		}
+		else if c == '(' {
+			tokens.push((Token::LParen, column));
+			i += 1;
+		}
+		else if c == ')' {
+			tokens.push((Token::RParen, column));
+			i += 1;
+		}
+		else if c == ',' {
+			tokens.push((Token::Comma, column));
+			i += 1;
+		}
+		else {
+			return Err(ParseError::new(column, &format!("unexpected character `{}`", c)));
+		}
+	}
+	tokens.push((Token::End, chars.len() + 1));
+	return Ok(tokens);
+}
+
+// Resolves a function name against the `symbol` table, ignoring case so that
+// both `Sin(t)` and `sin(t)` are accepted.
+fn function(name: &str) -> Option<EnumExpr> {
+	let table = symbol::default();
+	for (key, sym) in table.0.iter() {
+		if sym.eq_ignore_ascii_case(name) {
+			return match key.as_str() {
+				"SQRT" => Some(EnumExpr::SQRT),
+				"SQUARE" => Some(EnumExpr::SQUARE),
+				"SIN" => Some(EnumExpr::SIN),
+				"COS" => Some(EnumExpr::COS),
+				"ASIN" => Some(EnumExpr::ASIN),
+				"ACOS" => Some(EnumExpr::ACOS),
+				"LOG" => Some(EnumExpr::LOG),
+				"INTEGRAL" => Some(EnumExpr::INTEGRAL),
+				_ => None,
+			};
+		}
+	}
+	return None;
+}
+
+struct Parser {
+	tokens: Vec<(Token, usize)>,
+	pos: usize,
+}
+
+impl Parser {
+	fn peek(&self) -> &Token {
+		&self.tokens[self.pos].0
+	}
+
+	fn column(&self) -> usize {
+		self.tokens[self.pos].1
+	}
+
+	fn next(&mut self) -> (Token, usize) {
+		let token = self.tokens[self.pos].clone();
+		if self.pos + 1 < self.tokens.len() {
+			self.pos += 1;
+		}
+		token
+	}
+
+	fn unexpected(&self) -> ParseError {
+		match self.peek() {
+			Token::End => ParseError::new(self.column(), "unexpected end of input"),
+			Token::Num(val) => ParseError::new(self.column(), &format!("unexpected number `{}`", val)),
+			Token::Name(name) => ParseError::new(self.column(), &format!("unexpected name `{}`", name)),
+			Token::Op(op) => ParseError::new(self.column(), &format!("unexpected operator `{}`", op)),
+			Token::LParen => ParseError::new(self.column(), "unexpected `(`"),
+			Token::RParen => ParseError::new(self.column(), "unexpected `)`"),
+			Token::Comma => ParseError::new(self.column(), "unexpected `,`"),
+		}
+	}
+
+	fn sum(&mut self) -> Result<Expr, ParseError> {
+		let mut lhs = self.product()?;
+		loop {
+			match self.peek() {
+				Token::Op('+') => {
+					self.next();
+					lhs = lhs.Plus(self.product()?);
+				}
+				Token::Op('-') => {
+					self.next();
+					lhs = lhs.Minus(self.product()?);
+				}
+				_ => return Ok(lhs),
+			}
+		}
+	}
+
+	fn product(&mut self) -> Result<Expr, ParseError> {
+		let mut lhs = self.unary()?;
+		loop {
+			match self.peek() {
+				Token::Op('*') => {
+					self.next();
+					lhs = lhs.Times(self.unary()?);
+				}
+				Token::Op('/') => {
+					self.next();
+					lhs = lhs.Div(self.unary()?);
+				}
+				_ => return Ok(lhs),
+			}
+		}
+	}
+
+	fn unary(&mut self) -> Result<Expr, ParseError> {
+		match self.peek() {
+			Token::Op('-') => {
+				self.next();
+				let inner = self.unary()?;
+				// `-2` is read as a negative constant rather than NEGATE(2)
+				if inner.op() == EnumExpr::CONSTANT {
+					return Ok(Expr::from(-inner.x.coefficient));
+				}
+				return Ok(inner.Negate());
+			}
+			Token::Op('+') => {
+				self.next();
+				return self.unary();
+			}
+			_ => return self.power(),
+		}
+	}
+
+	fn power(&mut self) -> Result<Expr, ParseError> {
+		let base = self.primary()?;
+		if *self.peek() == Token::Op('^') {
+			self.next();
+			let exponent = self.unary()?;
+			return Ok(base.Pow(exponent));
+		}
+		return Ok(base);
+	}
+
+	fn primary(&mut self) -> Result<Expr, ParseError> {
+		match self.peek().clone() {
+			Token::Num(val) => {
+				self.next();
+				return Ok(Expr::from(val));
+			}
+			Token::Name(name) => {
+				let column = self.column();
+				self.next();
+				if *self.peek() != Token::LParen {
+					return Ok(Expr::from(name));
+				}
+				let op = match function(&name) {
+					Some(op) => op,
+					None => return Err(ParseError::new(column, &format!("unknown function `{}`", name))),
+				};
+				self.next();
+				let arg = self.sum()?;
+				if op == EnumExpr::INTEGRAL {
+					return self.integral(arg);
+				}
+				self.close()?;
+				let result = match op {
+					EnumExpr::SQRT => arg.Sqrt(),
+					EnumExpr::SQUARE => arg.Square(),
+					EnumExpr::SIN => arg.Sin(),
+					EnumExpr::COS => arg.Cos(),
+					EnumExpr::ASIN => arg.ASin(),
+					EnumExpr::LOG => arg.Log(),
+					_ => arg.ACos(),
+				};
+				return Ok(result);
+			}
+			Token::LParen => {
+				self.next();
+				let inner = self.sum()?;
+				self.close()?;
+				return Ok(inner);
+			}
+			_ => return Err(self.unexpected()),
+		}
+	}
+
+	// the rest of `integrate(f, x)` or `integrate(f, x, lower, upper)` after `f`
+	fn integral(&mut self, integrand: Expr) -> Result<Expr, ParseError> {
+		self.comma()?;
+		let x = match self.peek().clone() {
+			Token::Name(name) => name,
+			_ => return Err(ParseError::new(self.column(), "expected the integration variable")),
+		};
+		self.next();
+		if *self.peek() != Token::Comma {
+			self.close()?;
+			return Ok(integrand.Integral(&x));
+		}
+		self.next();
+		let lower = self.sum()?;
+		self.comma()?;
+		let upper = self.sum()?;
+		self.close()?;
+		return Ok(integrand.Definite(&x, lower, upper));
+	}
+
+	fn comma(&mut self) -> Result<(), ParseError> {
+		if *self.peek() != Token::Comma {
+			return Err(ParseError::new(self.column(), "expected `,`"));
+		}
+		self.next();
+		return Ok(());
+	}
+
+	fn close(&mut self) -> Result<(), ParseError> {
+		match self.peek() {
+			Token::RParen => {
+				self.next();
+				Ok(())
+			}
+			Token::End => Err(ParseError::new(self.column(), "missing `)`")),
+			_ => Err(self.unexpected()),
+		}
+	}
+}
+
+impl Expr {
+	pub fn parse(input: &str) -> Result<Expr, ParseError> {
+		let tokens = tokenize(input)?;
+		let mut parser = Parser { tokens, pos: 0 };
+		if *parser.peek() == Token::End {
+			return Err(ParseError::new(parser.column(), "empty expression"));
+		}
+		let result = parser.sum()?;
+		if *parser.peek() != Token::End {
+			return Err(parser.unexpected());
+		}
+		return Ok(result);
+	}
+}
+
+impl FromStr for Expr {
+	type Err = ParseError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Expr::parse(s)
+	}
+}
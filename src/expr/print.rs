@@ -0,0 +1,212 @@
+use num_notation::*;
+
+use crate::expr::expr::*;
+
+// Precedence-aware printing of `Expr` trees.
+//
+// Every node gets a binding strength, a child is wrapped in parentheses only
+// when it binds weaker than its parent requires. Unit coefficients and unit
+// exponents of PARAM terms are left out.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintStyle {
+	Ascii,   // 3*x^2*y - sin(t)/2
+	Unicode, // 3·x²·y - sin(t)/2
+	// 3*x^2*y-sin(t)/2, reads back through `Expr::parse` into the same tree when
+	// the tree came from the parser; a PARAM term such as `3*x^2` reads back as
+	// a product, equivalent to it and equal to it after `simplify`
+	Compact,
+}
+
+pub(crate) const SUM: u8 = 1;
+pub(crate) const PRODUCT: u8 = 2;
+pub(crate) const UNARY: u8 = 3;
+pub(crate) const POWER: u8 = 4;
+pub(crate) const ATOM: u8 = 5;
+
+fn superscript(s: &str) -> Option<String> {
+	let mut result: String = String::new();
+	for c in s.chars() {
+		result.push(match c {
+			'0' => '⁰',
+			'1' => '¹',
+			'2' => '²',
+			'3' => '³',
+			'4' => '⁴',
+			'5' => '⁵',
+			'6' => '⁶',
+			'7' => '⁷',
+			'8' => '⁸',
+			'9' => '⁹',
+			'-' => '⁻',
+			_ => return None,
+		});
+	}
+	return Some(result);
+}
+
+fn times(style: PrintStyle) -> &'static str {
+	match style {
+		PrintStyle::Unicode => "·",
+		_ => "*",
+	}
+}
+
+fn function_name(op: &EnumExpr) -> String {
+	let table = symbol::default();
+	return table.0.get(&op.to_string()).unwrap().to_lowercase();
+}
+
+fn wrap(s: String, needed: bool) -> String {
+	if needed {
+		return format!("({})", s);
+	}
+	return s;
+}
+
+fn exponent(base: &str, n: &Number, style: PrintStyle) -> String {
+	let text: String = n.to_string();
+	if style == PrintStyle::Unicode && let Some(sup) = superscript(&text) {
+		return format!("{}{}", base, sup);
+	}
+	return format!("{}^{}", base, wrap(text, *n < 0.0));
+}
+
+// Prints `c * v1^e1 * ...` without unit coefficient, unit exponents or zero exponents.
+pub(crate) fn term_string(x: &Term, style: PrintStyle) -> String {
+	let coefficient: f64 = f64::from(x.coefficient.clone());
+	let factors: Vec<String> = x.variables.iter()
+		.filter(|(_, n)| **n != 0.0)
+		.map(|(s, n)| if *n == 1.0 { s.clone() } else { exponent(s, n, style) })
+		.collect();
+	if factors.is_empty() {
+		return x.coefficient.to_string();
+	}
+	let body: String = factors.join(times(style));
+	if coefficient == 1.0 {
+		return body;
+	}
+	if coefficient == -1.0 {
+		return format!("-{}", body);
+	}
+	return format!("{}{}{}", x.coefficient, times(style), body);
+}
+
+pub(crate) fn term_precedence(x: &Term) -> u8 {
+	let coefficient: f64 = f64::from(x.coefficient.clone());
+	let factors: Vec<&Number> = x.variables.values().filter(|n| **n != 0.0).collect();
+	if coefficient < 0.0 {
+		return UNARY;
+	}
+	if factors.is_empty() {
+		return ATOM;
+	}
+	if coefficient != 1.0 || factors.len() > 1 {
+		return PRODUCT;
+	}
+	if *factors[0] != 1.0 {
+		return POWER;
+	}
+	return ATOM;
+}
+
+fn precedence(e: &Expr, style: PrintStyle) -> u8 {
+	match e.op {
+		EnumExpr::CONSTANT | EnumExpr::PARAM => term_precedence(&e.x),
+		EnumExpr::PLUS | EnumExpr::MINUS => SUM,
+		EnumExpr::TIMES | EnumExpr::DIV => PRODUCT,
+		EnumExpr::NEGATE => UNARY,
+		EnumExpr::POW => POWER,
+		EnumExpr::SQUARE if style == PrintStyle::Unicode => POWER,
+		_ => ATOM,
+	}
+}
+
+fn binary(e: &Expr, style: PrintStyle) -> String {
+	let a: &Expr = e.a.as_ref().unwrap();
+	let b: &Expr = e.b.as_ref().unwrap();
+	let own: u8 = precedence(e, style);
+	let (pa, pb) = (precedence(a, style), precedence(b, style));
+
+	// PLUS and TIMES are associative, but the compact form keeps the tree shape
+	let left_assoc: bool = matches!(e.op, EnumExpr::MINUS | EnumExpr::DIV) || style == PrintStyle::Compact;
+	let (left_paren, right_paren): (bool, bool) = match e.op {
+		EnumExpr::POW => (pa <= own, pb < own),
+		_ => (pa < own, pb < own || (left_assoc && pb == own) || pb == UNARY),
+	};
+	let lhs: String = wrap(write(a, style), left_paren);
+	let rhs: String = wrap(write(b, style), right_paren);
+
+	let spaced: bool = style != PrintStyle::Compact;
+	match e.op {
+		EnumExpr::PLUS if spaced => format!("{} + {}", lhs, rhs),
+		EnumExpr::PLUS => format!("{}+{}", lhs, rhs),
+		EnumExpr::MINUS if spaced => format!("{} - {}", lhs, rhs),
+		EnumExpr::MINUS => format!("{}-{}", lhs, rhs),
+		EnumExpr::TIMES => format!("{}{}{}", lhs, times(style), rhs),
+		EnumExpr::DIV => format!("{}/{}", lhs, rhs),
+		_ => {
+			if style == PrintStyle::Unicode {
+				if b.op == EnumExpr::CONSTANT {
+					return exponent(&lhs, &b.x.coefficient, style);
+				}
+				// superscripts only cover plain numbers, anything else stays on the line
+				return format!("{}^{}", lhs, wrap(write(b, PrintStyle::Ascii), pb < ATOM));
+			}
+			format!("{}^{}", lhs, rhs)
+		}
+	}
+}
+
+fn unary(e: &Expr, style: PrintStyle) -> String {
+	let a: &Expr = e.a.as_ref().unwrap();
+	let inner: String = write(a, style);
+	match e.op {
+		EnumExpr::NEGATE => format!("-{}", wrap(inner, precedence(a, style) <= UNARY)),
+		EnumExpr::SQRT if style == PrintStyle::Unicode => format!("√({})", inner),
+		EnumExpr::SQUARE if style == PrintStyle::Unicode => format!("{}²", wrap(inner, precedence(a, style) <= POWER)),
+		_ => format!("{}({})", function_name(&e.op), inner),
+	}
+}
+
+// integrate(f, x) and integrate(f, x, lower, upper)
+fn integral(e: &Expr, style: PrintStyle) -> String {
+	let separator: &str = if style == PrintStyle::Compact { "," } else { ", " };
+	let x: String = e.integration_variable();
+	let name: String = function_name(&EnumExpr::INTEGRAL);
+	if e.op == EnumExpr::INTEGRAL {
+		return format!("{}({}{}{})", name, write(e.a.as_ref().unwrap(), style), separator, x);
+	}
+	let integrand: &Expr = e.a.as_ref().unwrap().a.as_ref().unwrap();
+	let (lower, upper) = e.bounds();
+	let parts: [String; 4] = [
+		write(integrand, style),
+		x,
+		write(lower, style),
+		write(upper, style),
+	];
+	return format!("{}({})", name, parts.join(separator));
+}
+
+pub(crate) fn write(e: &Expr, style: PrintStyle) -> String {
+	if e.op == EnumExpr::INTEGRAL || e.op == EnumExpr::DEFINITE {
+		return integral(e, style);
+	}
+	match e.children() {
+		0 => term_string(&e.x, style),
+		1 => unary(e, style),
+		_ => binary(e, style),
+	}
+}
+
+impl Expr {
+	pub fn pretty(&self, style: PrintStyle) -> String {
+		return write(self, style);
+	}
+}
+
+impl Term {
+	pub fn pretty(&self, style: PrintStyle) -> String {
+		return term_string(self, style);
+	}
+}
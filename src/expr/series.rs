@@ -0,0 +1,346 @@
+use std::collections::*;
+use std::fmt::Display;
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::mono::mono::*;
+use crate::poly::poly::Poly;
+
+// Taylor and Laurent series of an expression in one variable.
+//
+// Series are built bottom up with t = x - point: leaves expand directly,
+// sums, products and quotients combine truncated coefficient lists, and a
+// function applied to a series u is composed from its symbolic derivatives at
+// u(point). A quotient whose denominator vanishes at the point has a pole and
+// yields negative exponents. Every list keeps a fixed number of significant
+// terms counted from its leading nonzero coefficient.
+//
+// Next to each coefficient the sum of the absolute values that went into it
+// is kept. A coefficient counts as zero when it is below `EPS` times that sum,
+// so cancellation is caught at any scale while small terms such as 1/23! stay.
+
+const EPS: f64 = 1e-12;
+const MAX_EXTRA_TERMS: usize = 32;
+
+// sum of c[k] t^(v + k), all zero when the series vanishes to the precision
+// kept; m[k] bounds the magnitude of the terms summed into c[k]
+#[derive(Debug, Clone)]
+struct Laurent {
+	v: i32,
+	c: Vec<f64>,
+	m: Vec<f64>,
+}
+
+impl Laurent {
+	fn constant(c: f64, n: usize) -> Laurent {
+		let mut coefficients: Vec<f64> = vec![0.0; n];
+		coefficients[0] = c;
+		return Laurent::exact(0, coefficients);
+	}
+
+	// coefficients without rounding error of their own
+	fn exact(v: i32, c: Vec<f64>) -> Laurent {
+		let m: Vec<f64> = c.iter().map(|c| c.abs()).collect();
+		return Laurent { v, c, m };
+	}
+
+	fn is_zero(&self, k: usize) -> bool {
+		return self.c[k].abs() <= EPS * self.m[k];
+	}
+
+	fn leading_zeros(&self) -> usize {
+		return (0..self.c.len()).take_while(|k| self.is_zero(*k)).count();
+	}
+
+	fn truncate(mut self, n: usize) -> Laurent {
+		self.c.resize(n, 0.0);
+		self.m.resize(n, 0.0);
+		return self;
+	}
+
+	// coefficient of t^k and its magnitude
+	fn at(&self, k: i32) -> (f64, f64) {
+		if k < self.v || k >= self.v + self.c.len() as i32 {
+			return (0.0, 0.0);
+		}
+		return (self.c[(k - self.v) as usize], self.m[(k - self.v) as usize]);
+	}
+
+	fn add(&self, other: &Laurent, sign: f64, n: usize) -> Laurent {
+		let v: i32 = self.v.min(other.v);
+		let mut result: Laurent = Laurent { v, c: vec![0.0; n], m: vec![0.0; n] };
+		for k in 0..n {
+			let ((a, ma), (b, mb)) = (self.at(v + k as i32), other.at(v + k as i32));
+			result.c[k] = a + sign * b;
+			result.m[k] = ma + mb;
+		}
+		return result;
+	}
+
+	fn mul(&self, other: &Laurent, n: usize) -> Laurent {
+		let mut result: Laurent = Laurent { v: self.v + other.v, c: vec![0.0; n], m: vec![0.0; n] };
+		for i in 0..n.min(self.c.len()) {
+			for j in 0..(n - i).min(other.c.len()) {
+				result.c[i + j] += self.c[i] * other.c[j];
+				result.m[i + j] += self.m[i] * other.m[j];
+			}
+		}
+		return result;
+	}
+
+	// `other` must have a nonzero leading coefficient
+	fn div(&self, other: &Laurent, n: usize) -> Laurent {
+		let mut result: Laurent = Laurent { v: self.v - other.v, c: vec![0.0; n], m: vec![0.0; n] };
+		for k in 0..n {
+			let mut acc: f64 = self.c.get(k).cloned().unwrap_or(0.0);
+			let mut magnitude: f64 = self.m.get(k).cloned().unwrap_or(0.0);
+			for j in 1..=k.min(other.c.len() - 1) {
+				acc -= other.c[j] * result.c[k - j];
+				magnitude += other.m[j] * result.m[k - j];
+			}
+			result.c[k] = acc / other.c[0];
+			result.m[k] = magnitude / other.c[0].abs();
+		}
+		return result;
+	}
+
+	fn scale(&self, s: f64) -> Laurent {
+		return Laurent {
+			v: self.v,
+			c: self.c.iter().map(|c| c * s).collect(),
+			m: self.m.iter().map(|m| m * s.abs()).collect(),
+		};
+	}
+}
+
+// f(u) for error messages, f as an expression in `y`
+fn composed(f: &Expr, u: &Expr) -> String {
+	return match f.subs(&BTreeMap::from([("y".to_string(), u.clone())])) {
+		Some(e) => e.to_string(),
+		None => f.to_string(),
+	};
+}
+
+struct Expansion<'a> {
+	var: &'a str,
+	point: f64,
+}
+
+impl Expansion<'_> {
+	// n significant terms, recomputing with more terms while leading ones cancel
+	fn normalized(&self, e: &Expr, n: usize) -> Result<Laurent, String> {
+		let mut m: usize = n;
+		loop {
+			let series: Laurent = self.laurent(e, m)?;
+			let z: usize = series.leading_zeros();
+			if z == m && m >= n + MAX_EXTRA_TERMS {
+				// identically zero as far as we can tell
+				return Ok(Laurent::exact(series.v + m as i32, vec![0.0; n]));
+			}
+			if m - z >= n || m >= n + MAX_EXTRA_TERMS {
+				return Ok(Laurent { v: series.v + z as i32, c: series.c[z..].to_vec(), m: series.m[z..].to_vec() }.truncate(n));
+			}
+			m = (n + z).max(m + 1).min(n + MAX_EXTRA_TERMS);
+		}
+	}
+
+	fn leaf(&self, x: &Term, n: usize) -> Result<Laurent, String> {
+		let coefficient: f64 = f64::from(x.coefficient.clone());
+		let mut p: f64 = 0.0;
+		for (s, exp) in x.variables.iter() {
+			if s != self.var {
+				return Err(format!("series needs an expression in {} alone, found {}", self.var, s));
+			}
+			p = f64::from(exp.clone());
+		}
+		if p == 0.0 {
+			return Ok(Laurent::constant(coefficient, n));
+		}
+		if self.point == 0.0 {
+			if p.fract() != 0.0 {
+				return Err(format!("{}^{} has a branch point at 0", self.var, p));
+			}
+			return Ok(Laurent { v: p as i32, ..Laurent::constant(coefficient, n) });
+		}
+		// (a + t)^p = sum binomial(p, k) a^(p - k) t^k
+		let mut c: Vec<f64> = vec![0.0; n];
+		let mut binomial: f64 = 1.0;
+		for (k, ck) in c.iter_mut().enumerate() {
+			*ck = coefficient * binomial * self.point.powf(p - k as f64);
+			binomial *= (p - k as f64) / (k as f64 + 1.0);
+		}
+		return Ok(Laurent::exact(0, c));
+	}
+
+	// f(u) = sum f^(k)(u0) / k! (u - u0)^k, f given as an expression in `y`
+	fn compose(&self, f: Expr, u: &Expr, n: usize) -> Result<Laurent, String> {
+		let inner: Laurent = self.normalized(u, n)?;
+		if inner.v < 0 {
+			return Err(format!("{} has an essential singularity at {} = {}", composed(&f, u), self.var, self.point));
+		}
+		let u0: f64 = inner.at(0).0;
+		let w: Laurent = inner.add(&Laurent::constant(u0, n), -1.0, n);
+		let mut result: Laurent = Laurent::exact(0, vec![0.0; n]);
+		let mut derivative: Expr = f.clone();
+		let mut power: Laurent = Laurent::constant(1.0, n);
+		let mut factorial: f64 = 1.0;
+		for k in 0..n {
+			let value: f64 = f64::from(derivative.Eval(&Variables::from([("y".to_string(), Number::Decimal(u0))])));
+			if !value.is_finite() {
+				return Err(format!("{} is not analytic at {} = {}", composed(&f, u), self.var, self.point));
+			}
+			result = result.add(&power.scale(value / factorial), 1.0, n);
+			derivative = derivative.partial_wrt("y").simplify();
+			power = power.mul(&w, n);
+			factorial *= (k + 1) as f64;
+		}
+		return Ok(result);
+	}
+
+	fn laurent(&self, e: &Expr, n: usize) -> Result<Laurent, String> {
+		let y: Expr = Expr::from("y");
+		let series: Laurent = match e.op {
+			EnumExpr::CONSTANT => Laurent::constant(f64::from(e.x.coefficient.clone()), n),
+			EnumExpr::PARAM => self.leaf(&e.x, n)?,
+			EnumExpr::PLUS | EnumExpr::MINUS => {
+				let a: Laurent = self.normalized(e.a.as_ref().unwrap(), n)?;
+				let b: Laurent = self.normalized(e.b.as_ref().unwrap(), n)?;
+				a.add(&b, if e.op == EnumExpr::PLUS { 1.0 } else { -1.0 }, n)
+			}
+			EnumExpr::NEGATE => self.normalized(e.a.as_ref().unwrap(), n)?.scale(-1.0),
+			EnumExpr::TIMES => {
+				let a: Laurent = self.normalized(e.a.as_ref().unwrap(), n)?;
+				a.mul(&self.normalized(e.b.as_ref().unwrap(), n)?, n)
+			}
+			EnumExpr::DIV => {
+				let b: Laurent = self.normalized(e.b.as_ref().unwrap(), n)?;
+				if b.is_zero(0) {
+					return Err("division by a series that vanishes identically".to_string());
+				}
+				self.normalized(e.a.as_ref().unwrap(), n)?.div(&b, n)
+			}
+			EnumExpr::POW => {
+				let base: &Expr = e.a.as_ref().unwrap();
+				let exponent: &Expr = e.b.as_ref().unwrap();
+				if !exponent.depends_on(self.var) {
+					let p: f64 = f64::from(exponent.Eval(&Variables::new()));
+					if p.fract() == 0.0 {
+						// integer powers keep poles: repeated products, inverted for p < 0
+						let b: Laurent = self.normalized(base, n)?;
+						let mut result: Laurent = Laurent::constant(1.0, n);
+						for _ in 0..(p.abs() as usize) {
+							result = result.mul(&b, n);
+						}
+						if p < 0.0 {
+							return Ok(Laurent::constant(1.0, n).div(&result, n));
+						}
+						return Ok(result);
+					}
+					return self.compose(y.Pow(exponent.clone()), base, n);
+				}
+				if !base.depends_on(self.var) {
+					return self.compose(base.clone().Pow(y), exponent, n);
+				}
+				return Err("series of a variable raised to a variable power is not supported".to_string());
+			}
+			EnumExpr::SQRT => self.compose(y.Sqrt(), e.a.as_ref().unwrap(), n)?,
+			EnumExpr::SQUARE => {
+				let a: Laurent = self.normalized(e.a.as_ref().unwrap(), n)?;
+				a.mul(&a, n)
+			}
+			EnumExpr::SIN => self.compose(y.Sin(), e.a.as_ref().unwrap(), n)?,
+			EnumExpr::COS => self.compose(y.Cos(), e.a.as_ref().unwrap(), n)?,
+			EnumExpr::ASIN => self.compose(y.ASin(), e.a.as_ref().unwrap(), n)?,
+			EnumExpr::ACOS => self.compose(y.ACos(), e.a.as_ref().unwrap(), n)?,
+			EnumExpr::LOG => self.compose(y.Log(), e.a.as_ref().unwrap(), n)?,
+			_ => return Err(format!("series of {} is not supported", e.op)),
+		};
+		return Ok(series);
+	}
+}
+
+// Truncated series in powers of `var - point`. The polynomial is written in
+// `var` itself, so at point 0 it reads as usual, `to_expr` shifts it back.
+#[derive(Debug, Clone)]
+pub struct Series {
+	pub var: String,
+	pub point: f64,
+	pub poly: Poly,
+	pub order: i32, // the remainder is O((var - point)^order)
+}
+
+impl Series {
+	pub fn to_expr(&self) -> Expr {
+		if self.point == 0.0 {
+			return self.poly.e();
+		}
+		let shift: Expr = Expr::from(self.var.as_str()).Minus(Expr::from(self.point));
+		return self.poly.e().subs(&BTreeMap::from([(self.var.clone(), shift)])).expect("a polynomial holds no integral");
+	}
+
+	// exponent of the leading term, negative for a pole
+	pub fn valuation(&self) -> Option<i32> {
+		return self.poly.monomials.values()
+			.map(|m| f64::from(m.variables().get(&self.var).cloned().unwrap_or(Number::Decimal(0.0))) as i32)
+			.min();
+	}
+
+	// coefficient of (var - point)^k
+	pub fn coefficient(&self, k: i32) -> f64 {
+		for m in self.poly.monomials.values() {
+			let exp: f64 = f64::from(m.variables().get(&self.var).cloned().unwrap_or(Number::Decimal(0.0)));
+			if exp == k as f64 {
+				return f64::from(m.coefficient().clone());
+			}
+		}
+		return 0.0;
+	}
+}
+
+// ascending powers, e.g. `0.5*(x - 1)^(-1) - 0.25 + O((x - 1)^1)`
+impl Display for Series {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let base: String = match self.point {
+			0.0 => self.var.clone(),
+			_ => format!("({} - {})", self.var, self.point),
+		};
+		let mut result: String = String::new();
+		for mono in self.poly.sorted_monomials().iter().rev() {
+			let mut c: Number = mono.coefficient().clone();
+			if !result.is_empty() {
+				if c < 0.0 {
+					c = -c;
+					result.push_str(" - ");
+				}
+				else {
+					result.push_str(" + ");
+				}
+			}
+			let exp: Number = mono.variables().get(&self.var).cloned().unwrap_or(Number::Decimal(0.0));
+			result.push_str(&Term::from((c, Variables::from([(base.clone(), exp)]))).to_string());
+		}
+		if !result.is_empty() {
+			result.push_str(" + ");
+		}
+		write!(f, "{}O({}^{})", result, base, self.order)
+	}
+}
+
+impl Expr {
+	// terms up to (var - point)^order, poles give negative exponents; branch
+	// points, essential singularities and other variables are reported as an error
+	pub fn series(&self, var: &str, point: f64, order: i32) -> Result<Series, String> {
+		let expansion: Expansion = Expansion { var, point };
+		let mut n: usize = 1;
+		let mut laurent: Laurent = expansion.normalized(self, n)?;
+		if laurent.v + n as i32 - 1 < order {
+			n = (order - laurent.v + 1) as usize;
+			laurent = expansion.normalized(self, n)?;
+		}
+		let mut poly: Poly = Poly::default();
+		poly.extend((0..laurent.c.len())
+			.filter(|k| laurent.v + *k as i32 <= order && !laurent.is_zero(*k))
+			.map(|k| Mono::from((Number::Decimal(laurent.c[k]), Variables::from([(var.to_string(), Number::Decimal((laurent.v + k as i32) as f64))])))));
+		return Ok(Series { var: var.to_string(), point, poly, order: order + 1 });
+	}
+}
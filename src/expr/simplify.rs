@@ -0,0 +1,324 @@
+use std::sync::Arc;
+use num_notation::*;
+
+use crate::expr::expr::*;
+
+// Algebraic simplification of `Expr` trees.
+//
+// Each pass simplifies the children first and then rewrites the node:
+// sums (PLUS, MINUS, NEGATE) are flattened into a list of coefficient/base
+// pairs so like terms merge, products (TIMES) are flattened into a numeric
+// coefficient, one merged `Term` and the remaining factors. Terms and factors
+// are sorted by their printed form, so the same value always rebuilds into the
+// same tree. `simplify` repeats passes until nothing changes or `MAX_PASSES`
+// is reached, so the result is not guaranteed to be a fixpoint.
+
+// no proof that the passes converge, the cap keeps `simplify` from looping
+const MAX_PASSES: usize = 64;
+
+fn constant(e: &Expr) -> Option<f64> {
+	if e.op == EnumExpr::CONSTANT || (e.op == EnumExpr::PARAM && e.x.variables.is_empty()) {
+		return Some(f64::from(e.x.coefficient.clone()));
+	}
+	return None;
+}
+
+fn param(coefficient: f64, variables: Variables) -> Expr {
+	if coefficient == 0.0 || variables.is_empty() {
+		return Expr::from(coefficient);
+	}
+	return Expr::from((Number::Decimal(coefficient), variables));
+}
+
+// A PARAM leaf keeps its exponents, zero exponents are dropped.
+fn clean_param(e: &Expr) -> Expr {
+	let variables: Variables = e.x.variables.iter()
+		.filter(|(_, exp)| **exp != 0.0)
+		.map(|(k, exp)| (k.clone(), exp.clone()))
+		.collect();
+	return param(f64::from(e.x.coefficient.clone()), variables);
+}
+
+// Splits a simplified term into its numeric coefficient and the remaining base,
+// so that `3*x*sin(y)` and `-x*sin(y)` share the base `x*sin(y)`.
+fn split_coefficient(e: &Expr) -> (f64, Expr) {
+	match e.op {
+		EnumExpr::PARAM => {
+			let mut base: Expr = e.clone();
+			base.x.coefficient = Number::Decimal(1.0);
+			return (f64::from(e.x.coefficient.clone()), base);
+		}
+		EnumExpr::TIMES => {
+			let left = e.a.as_ref().unwrap();
+			let right: Expr = e.b.as_ref().unwrap().as_ref().clone();
+			if left.op == EnumExpr::CONSTANT {
+				return (f64::from(left.x.coefficient.clone()), right);
+			}
+			if left.op == EnumExpr::PARAM {
+				let (c, base) = split_coefficient(left);
+				return (c, base.Times(right));
+			}
+			return (1.0, e.clone());
+		}
+		_ => return (1.0, e.clone()),
+	}
+}
+
+// Inverse of `split_coefficient`.
+fn scale(base: Expr, c: f64) -> Expr {
+	if c == 1.0 {
+		return base;
+	}
+	match base.op {
+		EnumExpr::PARAM => {
+			return param(c * f64::from(base.x.coefficient.clone()), base.x.variables.clone());
+		}
+		EnumExpr::TIMES if base.a.as_ref().unwrap().op == EnumExpr::PARAM => {
+			let left: Expr = scale(base.a.as_ref().unwrap().as_ref().clone(), c);
+			return left.Times(base.b.as_ref().unwrap().as_ref().clone());
+		}
+		_ => return Expr::from(c).Times(base),
+	}
+}
+
+fn collect_sum(e: &Expr, sign: f64, constant_part: &mut f64, terms: &mut Vec<(Expr, f64)>) {
+	match e.op {
+		EnumExpr::PLUS => {
+			collect_sum(e.a.as_ref().unwrap(), sign, constant_part, terms);
+			collect_sum(e.b.as_ref().unwrap(), sign, constant_part, terms);
+		}
+		EnumExpr::MINUS => {
+			collect_sum(e.a.as_ref().unwrap(), sign, constant_part, terms);
+			collect_sum(e.b.as_ref().unwrap(), -sign, constant_part, terms);
+		}
+		EnumExpr::NEGATE => {
+			collect_sum(e.a.as_ref().unwrap(), -sign, constant_part, terms);
+		}
+		_ => {
+			if let Some(c) = constant(e) {
+				*constant_part += sign * c;
+				return;
+			}
+			let (c, base) = split_coefficient(e);
+			for (other, coefficient) in terms.iter_mut() {
+				if *other == base {
+					*coefficient += sign * c;
+					return;
+				}
+			}
+			terms.push((base, sign * c));
+		}
+	}
+}
+
+fn rebuild_sum(constant_part: f64, mut terms: Vec<(Expr, f64)>) -> Expr {
+	terms.retain(|(_, c)| *c != 0.0);
+	terms.sort_by_key(|(base, _)| base.to_string());
+
+	let mut result: Option<Expr> = None;
+	for (base, c) in terms.into_iter() {
+		result = Some(match result {
+			None if c < 0.0 => scale(base, -c).Negate(),
+			None => scale(base, c),
+			Some(acc) if c < 0.0 => acc.Minus(scale(base, -c)),
+			Some(acc) => acc.Plus(scale(base, c)),
+		});
+	}
+	match result {
+		None => Expr::from(constant_part),
+		Some(acc) if constant_part > 0.0 => acc.Plus(Expr::from(constant_part)),
+		Some(acc) if constant_part < 0.0 => acc.Minus(Expr::from(-constant_part)),
+		Some(acc) => acc,
+	}
+}
+
+fn collect_product(e: &Expr, coefficient: &mut f64, monomial: &mut Variables, factors: &mut Vec<Expr>) {
+	match e.op {
+		EnumExpr::TIMES => {
+			collect_product(e.a.as_ref().unwrap(), coefficient, monomial, factors);
+			collect_product(e.b.as_ref().unwrap(), coefficient, monomial, factors);
+		}
+		EnumExpr::CONSTANT => {
+			*coefficient *= f64::from(e.x.coefficient.clone());
+		}
+		EnumExpr::PARAM => {
+			*coefficient *= f64::from(e.x.coefficient.clone());
+			for (k, exp) in e.x.variables.iter() {
+				let total: Number = match monomial.get(k) {
+					Some(prev) => prev.clone() + exp.clone(),
+					None => exp.clone(),
+				};
+				if total == 0.0 {
+					monomial.remove(k);
+				}
+				else {
+					monomial.insert(k.clone(), total);
+				}
+			}
+		}
+		EnumExpr::NEGATE => {
+			*coefficient = -*coefficient;
+			collect_product(e.a.as_ref().unwrap(), coefficient, monomial, factors);
+		}
+		_ => factors.push(e.clone()),
+	}
+}
+
+fn rebuild_product(coefficient: f64, monomial: Variables, mut factors: Vec<Expr>) -> Expr {
+	if coefficient == 0.0 {
+		return Expr::from(0.0);
+	}
+	factors.sort_by_key(|f| f.to_string());
+
+	// right nested so the leading coefficient stays reachable from the root
+	let mut rest: Option<Expr> = None;
+	for f in factors.into_iter().rev() {
+		rest = Some(match rest {
+			None => f,
+			Some(acc) => f.Times(acc),
+		});
+	}
+	match rest {
+		None => param(coefficient, monomial),
+		Some(acc) => {
+			if !monomial.is_empty() {
+				return param(coefficient, monomial).Times(acc);
+			}
+			if coefficient < 0.0 {
+				return scale(acc, -coefficient).Negate();
+			}
+			return scale(acc, coefficient);
+		}
+	}
+}
+
+fn simplify_pow(base: Expr, exponent: Expr) -> Expr {
+	match (constant(&base), constant(&exponent)) {
+		(_, Some(0.0)) => Expr::from(1.0),
+		(_, Some(1.0)) => base,
+		(Some(b), Some(n)) => Expr::from(b.powf(n)),
+		(Some(1.0), None) => Expr::from(1.0),
+		(None, Some(n)) if base.op == EnumExpr::PARAM && n.fract() == 0.0 => {
+			// (c x^a y^b)^n = c^n x^(an) y^(bn) for integer n
+			let variables: Variables = base.x.variables.iter()
+				.map(|(k, exp)| (k.clone(), exp.clone() * n))
+				.collect();
+			return param(f64::from(base.x.coefficient.clone()).powf(n), variables);
+		}
+		_ => base.Pow(exponent),
+	}
+}
+
+fn simplify_unary(op: EnumExpr, a: Expr) -> Expr {
+	let value: Option<f64> = constant(&a);
+	match op {
+		EnumExpr::SQUARE => {
+			if a.op == EnumExpr::SQRT {
+				return a.a.as_ref().unwrap().as_ref().clone();
+			}
+			if value.is_some() || a.op == EnumExpr::PARAM {
+				return simplify_pow(a, Expr::from(2.0));
+			}
+			return a.Square();
+		}
+		EnumExpr::SQRT => match value {
+			Some(c) if c >= 0.0 && c.sqrt().fract() == 0.0 => Expr::from(c.sqrt()),
+			_ => a.Sqrt(),
+		},
+		EnumExpr::SIN => match value {
+			Some(0.0) => Expr::from(0.0),
+			_ => a.Sin(),
+		},
+		EnumExpr::COS => match value {
+			Some(0.0) => Expr::from(1.0),
+			_ => a.Cos(),
+		},
+		EnumExpr::ASIN => match value {
+			Some(0.0) => Expr::from(0.0),
+			_ => a.ASin(),
+		},
+		EnumExpr::ACOS => match value {
+			Some(1.0) => Expr::from(0.0),
+			_ => a.ACos(),
+		},
+		EnumExpr::LOG => match value {
+			Some(1.0) => Expr::from(0.0),
+			_ => a.Log(),
+		},
+		_ => panic!("Unknown operation"),
+	}
+}
+
+fn simplify_div(a: Expr, b: Expr) -> Expr {
+	match (constant(&a), constant(&b)) {
+		(_, Some(1.0)) => a,
+		(Some(n), Some(d)) if d != 0.0 => Expr::from(n / d),
+		(Some(0.0), None) => Expr::from(0.0),
+		(_, Some(-1.0)) => simplify_once(&a.Negate()),
+		_ => {
+			if a == b {
+				return Expr::from(1.0);
+			}
+			return a.Div(b);
+		}
+	}
+}
+
+fn simplify_once(e: &Expr) -> Expr {
+	match e.op {
+		EnumExpr::CONSTANT => e.clone(),
+		EnumExpr::PARAM => clean_param(e),
+		EnumExpr::PLUS | EnumExpr::MINUS | EnumExpr::NEGATE => {
+			let mut node: Expr = e.clone();
+			node.a = Some(Arc::new(simplify_once(e.a.as_ref().unwrap())));
+			if e.children() == 2 {
+				node.b = Some(Arc::new(simplify_once(e.b.as_ref().unwrap())));
+			}
+			let mut constant_part: f64 = 0.0;
+			let mut terms: Vec<(Expr, f64)> = Vec::new();
+			collect_sum(&node, 1.0, &mut constant_part, &mut terms);
+			return rebuild_sum(constant_part, terms);
+		}
+		EnumExpr::TIMES => {
+			let a: Expr = simplify_once(e.a.as_ref().unwrap());
+			let b: Expr = simplify_once(e.b.as_ref().unwrap());
+			let mut coefficient: f64 = 1.0;
+			let mut monomial: Variables = Variables::new();
+			let mut factors: Vec<Expr> = Vec::new();
+			collect_product(&a.Times(b), &mut coefficient, &mut monomial, &mut factors);
+			return rebuild_product(coefficient, monomial, factors);
+		}
+		EnumExpr::DIV => simplify_div(simplify_once(e.a.as_ref().unwrap()), simplify_once(e.b.as_ref().unwrap())),
+		EnumExpr::POW => simplify_pow(simplify_once(e.a.as_ref().unwrap()), simplify_once(e.b.as_ref().unwrap())),
+		EnumExpr::INTEGRAL => {
+			let mut node: Expr = e.clone();
+			node.a = Some(Arc::new(simplify_once(e.a.as_ref().unwrap())));
+			return node;
+		}
+		EnumExpr::DEFINITE => {
+			let (lower, upper) = e.bounds();
+			let mut node: Expr = e.clone();
+			node.a = Some(Arc::new(simplify_once(e.a.as_ref().unwrap())));
+			node.b = Some(Arc::new(simplify_once(lower)));
+			node.c = Some(Arc::new(simplify_once(upper)));
+			return node;
+		}
+		_ => simplify_unary(e.op.clone(), simplify_once(e.a.as_ref().unwrap())),
+	}
+}
+
+impl Expr {
+	// Simplifies until a fixpoint is reached, `e.simplify().simplify() == e.simplify()`,
+	// or gives up after `MAX_PASSES` passes and returns the last pass' result.
+	pub fn simplify(&self) -> Expr {
+		let mut current: Expr = simplify_once(self);
+		for _ in 0..MAX_PASSES {
+			let next: Expr = simplify_once(&current);
+			if next == current {
+				break;
+			}
+			current = next;
+		}
+		return current;
+	}
+}
@@ -0,0 +1,82 @@
+use std::collections::*;
+use std::sync::Arc;
+use num_notation::*;
+
+use crate::expr::expr::*;
+
+// Substitution of variables by expressions.
+//
+// A PARAM leaf packs a coefficient and several variables with exponents, so
+// substituting into it splits the term: variables that stay keep their place
+// in a smaller PARAM leaf, substituted ones become `value` or `value^exp`
+// factors multiplied onto it.
+
+fn power(value: &Expr, exp: &Number) -> Expr {
+	if *exp == 1.0 {
+		return value.clone();
+	}
+	if *exp == 2.0 {
+		return value.clone().Square();
+	}
+	return value.clone().Pow(Expr::from(exp.clone()));
+}
+
+fn subs_term(x: &Term, values: &BTreeMap<String, Expr>) -> Expr {
+	let mut kept: Variables = Variables::new();
+	let mut factors: Vec<Expr> = Vec::new();
+	for (s, exp) in x.variables.iter() {
+		match values.get(s) {
+			Some(value) if *exp != 0.0 => factors.push(power(value, exp)),
+			Some(_) => {},
+			None => {
+				kept.insert(s.clone(), exp.clone());
+			}
+		}
+	}
+
+	let mut result: Option<Expr> = None;
+	if !kept.is_empty() {
+		result = Some(Expr::from((x.coefficient.clone(), kept)));
+	}
+	else if x.coefficient != 1.0 || factors.is_empty() {
+		result = Some(Expr::from(x.coefficient.clone()));
+	}
+	for factor in factors.into_iter() {
+		result = match result {
+			Some(acc) => Some(acc.Times(factor)),
+			None => Some(factor),
+		};
+	}
+	return result.unwrap();
+}
+
+impl Expr {
+	// replaces every variable named in `values`, e.g. x -> 2*t + 1, None when
+	// that would replace the variable of an indefinite integral
+	pub fn subs(&self, values: &BTreeMap<String, Expr>) -> Option<Expr> {
+		if !values.keys().any(|k| self.depends_on(k)) {
+			return Some(self.clone());
+		}
+		let mut node: Expr = self.clone();
+		match self.children() {
+			0 => return Some(subs_term(&self.x, values)),
+			_ if self.op == EnumExpr::INTEGRAL && values.contains_key(&self.integration_variable()) => return None,
+			3 => {
+				// the integration variable is bound, only the other variables are replaced
+				let mut free: BTreeMap<String, Expr> = values.clone();
+				free.remove(&self.integration_variable());
+				let (lower, upper) = self.bounds();
+				node.a = Some(Arc::new(self.a.as_ref().unwrap().subs(&free)?));
+				node.b = Some(Arc::new(lower.subs(values)?));
+				node.c = Some(Arc::new(upper.subs(values)?));
+			}
+			n => {
+				node.a = Some(Arc::new(self.a.as_ref().unwrap().subs(values)?));
+				if n == 2 {
+					node.b = Some(Arc::new(self.b.as_ref().unwrap().subs(values)?));
+				}
+			}
+		}
+		return Some(node);
+	}
+}
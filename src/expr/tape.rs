@@ -0,0 +1,196 @@
+use std::collections::*;
+use num_notation::*;
+
+use crate::expr::expr::*;
+use crate::expr::compile::CompileError;
+
+// Reverse mode automatic differentiation.
+//
+// The tree is recorded once as a tape with children before their parents. A
+// node reached through several `Arc`s is recorded once, so a shared subtree
+// is evaluated once and its adjoint collects the contributions of all its
+// parents. `gradient` runs the tape forward for the values, then backward
+// for the adjoints, which gives every partial derivative in two passes
+// whatever the number of variables. A variable without a slot or a value is
+// reported as a `CompileError`.
+
+#[derive(Debug, Clone)]
+enum Node {
+	Constant(f64),
+	// coefficient times the product of values[slot]^exp
+	Term(f64, Vec<(usize, f64)>),
+	Binary(EnumExpr, usize, usize),
+	Unary(EnumExpr, usize),
+	// INTEGRAL or DEFINITE, evaluated by `Eval` and differentiated symbolically
+	Integral(Expr),
+}
+
+#[derive(Debug, Clone)]
+pub struct Tape {
+	variables: Vec<String>,
+	nodes: Vec<Node>,
+}
+
+impl Tape {
+	fn record(&mut self, e: &Expr, recorded: &mut HashMap<*const Expr, usize>) -> Result<usize, CompileError> {
+		let key: *const Expr = e;
+		if let Some(i) = recorded.get(&key) {
+			return Ok(*i);
+		}
+		let node: Node = match e.op {
+			EnumExpr::CONSTANT => Node::Constant(f64::from(e.x.coefficient.clone())),
+			EnumExpr::PARAM => {
+				let mut powers: Vec<(usize, f64)> = Vec::new();
+				for (name, exp) in e.x.variables.iter() {
+					match self.variables.iter().position(|v| v == name) {
+						Some(slot) => powers.push((slot, f64::from(exp.clone()))),
+						None => return Err(CompileError::new(e, format!("variable {} is not bound to a slot", name))),
+					}
+				}
+				Node::Term(f64::from(e.x.coefficient.clone()), powers)
+			}
+			EnumExpr::PLUS | EnumExpr::MINUS | EnumExpr::TIMES | EnumExpr::DIV | EnumExpr::POW => {
+				let a: usize = self.record(e.a.as_ref().unwrap(), recorded)?;
+				let b: usize = self.record(e.b.as_ref().unwrap(), recorded)?;
+				Node::Binary(e.op.clone(), a, b)
+			}
+			EnumExpr::NEGATE | EnumExpr::SQRT | EnumExpr::SQUARE | EnumExpr::SIN | EnumExpr::COS | EnumExpr::ASIN | EnumExpr::ACOS | EnumExpr::LOG => {
+				Node::Unary(e.op.clone(), self.record(e.a.as_ref().unwrap(), recorded)?)
+			}
+			EnumExpr::INTEGRAL | EnumExpr::DEFINITE => Node::Integral(e.clone()),
+			_ => return Err(CompileError::new(e, format!("operation {} cannot be recorded", e.op))),
+		};
+		self.nodes.push(node);
+		recorded.insert(key, self.nodes.len() - 1);
+		return Ok(self.nodes.len() - 1);
+	}
+
+	pub fn variables(&self) -> &[String] {
+		return &self.variables;
+	}
+
+	// number of recorded nodes, shared subtrees count once
+	pub fn len(&self) -> usize {
+		return self.nodes.len();
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.nodes.is_empty();
+	}
+
+	fn point(&self, values: &[f64]) -> Variables {
+		return self.variables.iter().zip(values.iter()).map(|(v, x)| (v.clone(), Number::Decimal(*x))).collect();
+	}
+
+	// value and gradient, `values[i]` and the i-th partial derivative belong to `variables()[i]`
+	pub fn gradient(&self, values: &[f64]) -> (f64, Vec<f64>) {
+		assert!(values.len() >= self.variables.len(), "expected {} values, got {}", self.variables.len(), values.len());
+		let mut v: Vec<f64> = Vec::with_capacity(self.nodes.len());
+		for node in self.nodes.iter() {
+			let value: f64 = match node {
+				Node::Constant(c) => *c,
+				Node::Term(c, powers) => powers.iter().fold(1.0, |r, (slot, exp)| r * values[*slot].powf(*exp)) * c,
+				Node::Binary(op, a, b) => match op {
+					EnumExpr::PLUS => v[*a] + v[*b],
+					EnumExpr::MINUS => v[*a] - v[*b],
+					EnumExpr::TIMES => v[*a] * v[*b],
+					EnumExpr::DIV => v[*a] / v[*b],
+					_ => v[*a].powf(v[*b]),
+				},
+				Node::Unary(op, a) => match op {
+					EnumExpr::NEGATE => -v[*a],
+					EnumExpr::SQRT => v[*a].sqrt(),
+					EnumExpr::SQUARE => v[*a].powi(2),
+					EnumExpr::SIN => v[*a].sin(),
+					EnumExpr::COS => v[*a].cos(),
+					EnumExpr::ASIN => v[*a].asin(),
+					EnumExpr::LOG => v[*a].ln(),
+					_ => v[*a].acos(),
+				},
+				Node::Integral(e) => f64::from(e.Eval(&self.point(values))),
+			};
+			v.push(value);
+		}
+
+		let mut adjoint: Vec<f64> = vec![0.0; self.nodes.len()];
+		let mut gradient: Vec<f64> = vec![0.0; self.variables.len()];
+		let root: usize = self.nodes.len() - 1;
+		adjoint[root] = 1.0;
+		for i in (0..self.nodes.len()).rev() {
+			let g: f64 = adjoint[i];
+			if g == 0.0 {
+				continue;
+			}
+			match &self.nodes[i] {
+				Node::Constant(_) => {},
+				Node::Term(c, powers) => {
+					for (j, (slot, exp)) in powers.iter().enumerate() {
+						// the other factors are multiplied in, not divided out, so a zero value is fine
+						let others: f64 = powers.iter().enumerate()
+							.filter(|(k, _)| *k != j)
+							.fold(1.0, |r, (_, (s, e))| r * values[*s].powf(*e));
+						gradient[*slot] += g * c * exp * values[*slot].powf(exp - 1.0) * others;
+					}
+				}
+				Node::Binary(op, a, b) => {
+					let (x, y) = (v[*a], v[*b]);
+					let (ga, gb): (f64, f64) = match op {
+						EnumExpr::PLUS => (g, g),
+						EnumExpr::MINUS => (g, -g),
+						EnumExpr::TIMES => (g * y, g * x),
+						EnumExpr::DIV => (g / y, -g * x / (y * y)),
+						_ => (g * y * x.powf(y - 1.0), g * v[i] * x.ln()),
+					};
+					adjoint[*a] += ga;
+					adjoint[*b] += gb;
+				}
+				Node::Unary(op, a) => {
+					let x: f64 = v[*a];
+					adjoint[*a] += g * match op {
+						EnumExpr::NEGATE => -1.0,
+						EnumExpr::SQRT => 0.5 / v[i],
+						EnumExpr::SQUARE => 2.0 * x,
+						EnumExpr::SIN => x.cos(),
+						EnumExpr::COS => -x.sin(),
+						EnumExpr::ASIN => 1.0 / (1.0 - x * x).sqrt(),
+						EnumExpr::LOG => 1.0 / x,
+						_ => -1.0 / (1.0 - x * x).sqrt(),
+					};
+				}
+				Node::Integral(e) => {
+					let point: Variables = self.point(values);
+					for (slot, name) in self.variables.iter().enumerate() {
+						if e.depends_on(name) {
+							gradient[slot] += g * f64::from(e.partial_wrt(name).Eval(&point));
+						}
+					}
+				}
+			}
+		}
+		return (v[root], gradient);
+	}
+}
+
+impl Expr {
+	// records the tape once for repeated gradients, `variables` fixes the slots
+	pub fn tape(&self, variables: &[&str]) -> Result<Tape, CompileError> {
+		let mut tape: Tape = Tape { variables: variables.iter().map(|v| v.to_string()).collect(), nodes: Vec::new() };
+		tape.record(self, &mut HashMap::new())?;
+		return Ok(tape);
+	}
+
+	// value and partial derivatives with respect to every free variable, by reverse mode
+	pub fn eval_with_gradient_reverse(&self, values: &Variables) -> Result<(f64, BTreeMap<String, f64>), CompileError> {
+		let variables: Vec<String> = self.free_variables().into_iter().collect();
+		let slots: Vec<&str> = variables.iter().map(|v| v.as_str()).collect();
+		let mut point: Vec<f64> = Vec::with_capacity(variables.len());
+		for v in variables.iter() {
+			match values.get(v) {
+				Some(value) => point.push(f64::from(value.clone())),
+				None => return Err(CompileError::new(self, format!("no value for variable {}", v))),
+			}
+		}
+		let (value, gradient) = self.tape(&slots)?.gradient(&point);
+		return Ok((value, variables.into_iter().zip(gradient).collect()));
+	}
+}
@@ -1,8 +1,12 @@
 pub mod expr;
 pub mod poly;
 pub mod mono;
+// the solver's placeholder types are not used yet
+#[allow(dead_code, clippy::module_inception)]
 pub mod solv;
 pub mod ring;
+pub mod export;
+pub mod coefficient;
 
 
 pub fn add(left: u64, right: u64) -> u64 {
@@ -35,4 +39,1014 @@ mod tests {
         println!("a Negate: {}", a.clone().Negate());
         println!("a ops: {}", a.clone().op());
     }
+
+    #[test]
+    fn parse_precedence() {
+        let e: Expr = Expr::parse("3*x^2*y - sin(t)/2").unwrap();
+        assert_eq!(e.op(), EnumExpr::MINUS);
+        assert_eq!(e.a().unwrap().op(), EnumExpr::TIMES);
+        assert_eq!(e.b().unwrap().op(), EnumExpr::DIV);
+        assert_eq!(e.b().unwrap().a().unwrap().op(), EnumExpr::SIN);
+
+        let n: Expr = "-x^2".parse().unwrap();
+        assert_eq!(n.op(), EnumExpr::NEGATE);
+        assert_eq!(n.a().unwrap().op(), EnumExpr::POW);
+
+        let p: Expr = Expr::parse("(a + b) * Sqr(c)").unwrap();
+        assert_eq!(p.op(), EnumExpr::TIMES);
+        assert_eq!(p.a().unwrap().op(), EnumExpr::PLUS);
+        assert_eq!(p.b().unwrap().op(), EnumExpr::SQUARE);
+    }
+
+    #[test]
+    fn parse_errors_report_column() {
+        assert_eq!(Expr::parse("x + $").unwrap_err().column, 5);
+        assert_eq!(Expr::parse("(x + 1").unwrap_err().column, 7);
+        assert_eq!(Expr::parse("2 * foo(x)").unwrap_err().column, 5);
+        assert_eq!(Expr::parse("x y").unwrap_err().column, 3);
+        assert!(Expr::parse("").is_err());
+    }
+
+    fn eval_at(e: &Expr, values: &[(&str, f64)]) -> f64 {
+        let vars: Variables = values.iter().map(|(k, v)| (k.to_string(), num_notation::Number::Decimal(*v))).collect();
+        return f64::from(e.Eval(&vars));
+    }
+
+    #[test]
+    fn partial_derivatives() {
+        let at: [(&str, f64); 2] = [("x", 0.7), ("y", -1.3)];
+
+        // PARAM leaf holding several variables: 3 x^2 y^3
+        let term: Expr = Expr::from((num_notation::Number::Decimal(3.0), vec!["x".to_string(), "y".to_string()], vec![num_notation::Number::Decimal(2.0), num_notation::Number::Decimal(3.0)]));
+        assert!((eval_at(&term.partial_wrt("x"), &at) - 6.0 * 0.7 * (-1.3f64).powi(3)).abs() < 1e-12);
+        assert!((eval_at(&term.mixed_partial(&["x", "y"]), &at) - 18.0 * 0.7 * (-1.3f64).powi(2)).abs() < 1e-12);
+        assert_eq!(eval_at(&term.partial_wrt("z"), &at), 0.0);
+
+        let e: Expr = Expr::parse("sin(x*y) / sqrt(x) + acos(x/2) - x^3").unwrap();
+        let expected = |x: f64, y: f64| (x * y).cos() * y / x.sqrt() - 0.5 * (x * y).sin() * x.powf(-1.5)
+            - 0.5 / (1.0 - x * x / 4.0).sqrt() - 3.0 * x * x;
+        assert!((eval_at(&e.partial_wrt("x"), &at) - expected(0.7, -1.3)).abs() < 1e-12);
+
+        let cube: Expr = Expr::parse("x^3").unwrap();
+        assert_eq!(eval_at(&cube.partial_wrt_n("x", 3), &at), 6.0);
+        assert_eq!(eval_at(&cube.partial_wrt_n("x", 4), &at), 0.0);
+
+        // variable base and exponent: d x^x = x^x (ln(x) + 1)
+        let tower: Expr = Expr::parse("x^x").unwrap();
+        assert!((eval_at(&tower.partial_wrt("x"), &at) - 0.7f64.powf(0.7) * (0.7f64.ln() + 1.0)).abs() < 1e-12);
+        let log: Expr = Expr::parse("log(x*y^2)").unwrap();
+        assert!((eval_at(&log.partial_wrt("y"), &at) - 2.0 / -1.3).abs() < 1e-12);
+        assert_eq!(Expr::parse("log(x)").unwrap().op(), EnumExpr::LOG);
+    }
+
+    #[test]
+    fn simplify_identities() {
+        let x: Expr = Expr::from("x");
+        let e: Expr = Expr::from(0.0).Plus(Expr::from(1.0).Times(x.clone()).Times(Expr::from(1.0)));
+        assert_eq!(e.simplify(), x);
+        assert_eq!(x.clone().Negate().Negate().simplify(), x);
+        assert_eq!(x.clone().Sqrt().Square().simplify(), x);
+
+        let merged: Expr = Expr::parse("x*y + 2*y*x - 3*x*y + sin(t) - sin(t)").unwrap().simplify();
+        assert_eq!(merged.op(), EnumExpr::CONSTANT);
+        assert_eq!(eval_at(&merged, &[]), 0.0);
+
+        let twice: Expr = Expr::parse("x + x").unwrap().simplify();
+        assert_eq!(twice, Expr::from((num_notation::Number::Decimal(2.0), "x".to_string(), num_notation::Number::Decimal(1.0))));
+
+        let folded: Expr = Expr::parse("(2 + 3) * x^2 * y / 1").unwrap().simplify();
+        assert_eq!(folded.op(), EnumExpr::PARAM);
+        assert_eq!(eval_at(&folded, &[("x", 2.0), ("y", 3.0)]), 60.0);
+    }
+
+    #[test]
+    fn simplify_is_idempotent() {
+        let inputs = ["3*x^2*y - sin(t)/2", "-(-(a - b)) * (c + 0)", "Sqr(Sqrt(x + 1)) - 1", "x*(y + z) - (z + y)*x"];
+        let at: [(&str, f64); 6] = [("x", 0.3), ("y", 1.7), ("z", -0.4), ("t", 2.1), ("a", 5.0), ("b", 1.5)];
+        for input in inputs.iter() {
+            let e: Expr = Expr::parse(input).unwrap();
+            let once: Expr = e.simplify();
+            assert_eq!(once.simplify(), once);
+            assert!((eval_at(&once, &at) - eval_at(&e, &at)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn structural_equality_and_hash() {
+        use std::collections::HashSet;
+        use num_notation::Number;
+
+        // same leaf built through different `From` impls
+        let leaf: Expr = Expr::from("x");
+        let term: Expr = Expr::from((Number::Decimal(1.0), "x".to_string(), Number::Decimal(1.0)));
+        assert_eq!(leaf, term);
+        // both print as `1`, but one is a CONSTANT and the other a PARAM without variables
+        assert_ne!(Expr::from(1.0), Expr::from((Number::Decimal(1.0), Variables::new())));
+        assert_ne!(Expr::parse("a + b*c").unwrap(), Expr::parse("(a + b)*c").unwrap());
+        assert_ne!(Expr::parse("x - y").unwrap(), Expr::parse("y - x").unwrap());
+
+        let mut set: HashSet<Expr> = HashSet::new();
+        set.insert(Expr::parse("sin(x) + 1").unwrap());
+        set.insert(Expr::parse("sin(x) + 1").unwrap());
+        set.insert(Expr::parse("1 + sin(x)").unwrap());
+        assert_eq!(set.len(), 2);
+
+        assert!(Expr::parse("2*x + y").unwrap().equivalent(&Expr::parse("y + x + x").unwrap()));
+        assert!(Expr::parse("-(-(a*b))").unwrap().equivalent(&Expr::parse("b*a").unwrap()));
+        assert!(!Expr::parse("x + 1").unwrap().equivalent(&Expr::parse("x").unwrap()));
+    }
+
+    #[test]
+    fn pretty_printing_styles() {
+        use expr::print::PrintStyle;
+
+        assert_eq!(Expr::parse("(a+b)*c").unwrap().to_string(), "(a + b)*c");
+        assert_eq!(Expr::parse("a+b*c").unwrap().to_string(), "a + b*c");
+        assert_eq!(Expr::parse("a-(b-c)").unwrap().to_string(), "a - (b - c)");
+        assert_eq!(Expr::parse("-(x*y)").unwrap().to_string(), "-(x*y)");
+        assert_eq!(Expr::parse("x + x*y").unwrap().simplify().to_string(), "x + x*y");
+
+        let e: Expr = Expr::parse("3*x^2*y - sin(t)/2").unwrap();
+        assert_eq!(e.to_string(), "3*x^2*y - sin(t)/2");
+        assert_eq!(e.pretty(PrintStyle::Unicode), "3·x²·y - sin(t)/2");
+        assert_eq!(e.pretty(PrintStyle::Compact), "3*x^2*y-sin(t)/2");
+
+        for input in ["a - (b - c)*d^-2", "-(x + 1)^(y/2)", "sqr(sqrt(x) + -3) * (a*(b*c))", "acos(-x)/(-2)"] {
+            let parsed: Expr = Expr::parse(input).unwrap();
+            assert_eq!(Expr::parse(&parsed.pretty(PrintStyle::Compact)).unwrap(), parsed);
+        }
+
+        // simplified trees pack coefficients and powers into PARAM terms, which read back as products
+        for input in ["3*x^2 - 2*y", "x*y*x + 2*sin(x)^2/3", "-(a - b)*4 + a^-1", "2*x^x - log(y*y)"] {
+            let simplified: Expr = Expr::parse(input).unwrap().simplify();
+            let parsed: Expr = Expr::parse(&simplified.pretty(PrintStyle::Compact)).unwrap();
+            assert!(parsed.equivalent(&simplified), "{}", input);
+            assert_eq!(parsed.simplify(), simplified, "{}", input);
+        }
+    }
+
+    #[test]
+    fn poly_prints_in_monomial_order() {
+        use std::collections::HashMap;
+        use poly::poly::Poly;
+
+        let terms: HashMap<String, Expr> = ["4", "x^2", "-2*y", "x*y^2"].iter()
+            .map(|s| (s.to_string(), Expr::parse(s).unwrap().simplify()))
+            .collect();
+        assert_eq!(Poly::from(terms).to_string(), "x*y^2 + x^2 - 2*y + 4");
+    }
+
+    #[test]
+    fn latex_export() {
+        use std::collections::{BTreeMap, HashMap};
+        use poly::poly::Poly;
+        use coefficient::rational::Rational;
+        use export::latex::*;
+
+        assert_eq!(Expr::parse("3*x^2*y - sin(t)/2").unwrap().to_latex(), "3 x^{2} y - \\frac{\\sin\\left(t\\right)}{2}");
+        assert_eq!(Expr::parse("sqrt(a + b) * asin(x)").unwrap().to_latex(), "\\sqrt{a + b} \\arcsin\\left(x\\right)");
+        assert_eq!(Expr::parse("(x + 1)^2 * 2").unwrap().to_latex(), "\\left(x + 1\\right)^{2} \\cdot 2");
+        assert_eq!(Expr::parse("theta_1 * -rate").unwrap().to_latex(), "\\theta_1 \\left(-\\mathrm{rate}\\right)");
+        assert_eq!(Expr::parse("Gamma*Delta + acos(x) - log(y)").unwrap().to_latex(), "\\Gamma \\Delta + \\arccos\\left(x\\right) - \\ln\\left(y\\right)");
+
+        let terms: HashMap<String, Expr> = ["x^2", "-2*y"].iter()
+            .map(|s| (s.to_string(), Expr::parse(s).unwrap().simplify()))
+            .collect();
+        let p: Poly = Poly::from(terms);
+        assert_eq!(p.to_latex(), "x^{2} - 2 y");
+        assert_eq!(p.sorted_monomials()[1].to_latex(), "-2 y");
+        assert_eq!(basis_to_latex(&[p.clone(), p]), "\\begin{align}\nx^{2} - 2 y &= 0 \\\\\nx^{2} - 2 y &= 0\n\\end{align}");
+
+        // exact coefficients render as fractions
+        let q: Poly<Rational> = Poly::try_from(&Expr::parse("x^2 - y/3 + 5/2").unwrap()).unwrap().to_rational().unwrap();
+        assert_eq!(q.to_latex(), "x^{2} - \\frac{1}{3} y + \\frac{5}{2}");
+        assert_eq!(q.sorted_monomials()[1].to_latex(), "-\\frac{1}{3} y");
+        assert_eq!(basis_to_latex(&[q]), "\\begin{align}\nx^{2} - \\frac{1}{3} y + \\frac{5}{2} &= 0\n\\end{align}");
+
+        let solution: BTreeMap<String, Expr> = BTreeMap::from([("x".to_string(), Expr::parse("1/2").unwrap())]);
+        assert_eq!(solution_to_latex(&solution), "\\begin{align}\nx &= \\frac{1}{2}\n\\end{align}");
+    }
+
+    #[test]
+    fn full_form_round_trip() {
+        let e: Expr = Expr::parse("3*x^2 + sin(t)").unwrap();
+        assert_eq!(e.to_full_form().unwrap(), "Plus[Times[3, Power[x, 2]], Sin[t]]");
+
+        for input in ["a - b/(c*d)", "-(x + 1)^(-2)", "acos(sqrt(y)) * cos(0.25)", "asin(-1.5e-3*z)"] {
+            let parsed: Expr = Expr::parse(input).unwrap();
+            assert_eq!(Expr::from_full_form(&parsed.to_full_form().unwrap()).unwrap(), parsed);
+        }
+
+        let square: Expr = Expr::parse("sqr(x) - 2*x*y^3").unwrap().simplify();
+        assert!(Expr::from_full_form(&square.to_full_form().unwrap()).unwrap().equivalent(&square));
+
+        let read: Expr = Expr::from_full_form("Times[Rational[1, 2], Power[x, -1*^1]]").unwrap();
+        assert_eq!(eval_at(&read, &[("x", 2.0)]), 0.5 * 2f64.powi(-10));
+        assert_eq!(Expr::from_full_form("Plus[x, Foo[y]]").unwrap_err().column, 9);
+        assert_eq!(Expr::from_full_form("Sin[x, y]").unwrap_err().column, 1);
+        assert_eq!(Expr::from_full_form("Plus[1, Rational[x, 2]]").unwrap_err().column, 9);
+        assert!(Expr::from_full_form("Rational[1, 0]").is_err());
+
+        let subscripted: Expr = Expr::parse("theta_1^2 - sin(theta_1)").unwrap();
+        assert_eq!(subscripted.to_full_form().unwrap(), "Subtract[Power[theta\\[LetterSpace]1, 2], Sin[theta\\[LetterSpace]1]]");
+        assert_eq!(Expr::from_full_form(&subscripted.to_full_form().unwrap()).unwrap(), subscripted);
+    }
+
+    #[test]
+    fn mathml_export() {
+        let e: Expr = Expr::parse("x^2/sin(t)").unwrap();
+        assert_eq!(e.to_mathml(), "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mfrac><mrow><msup><mrow><mi>x</mi></mrow><mrow><mn>2</mn></mrow></msup></mrow><mrow><mrow><mi>sin</mi><mo>&#x2061;</mo><mrow><mo>(</mo><mi>t</mi><mo>)</mo></mrow></mrow></mrow></mfrac></math>");
+        let product: String = Expr::parse("(a + b)*2").unwrap().to_mathml();
+        assert!(product.contains("<mo>(</mo><mrow><mi>a</mi><mo>+</mo><mi>b</mi></mrow><mo>)</mo>"));
+        assert!(product.contains("<mo>&#x22C5;</mo><mn>2</mn>"));
+        assert_eq!(Expr::from("a<b&c".to_string()).to_mathml(), "<math xmlns=\"http://www.w3.org/1998/Math/MathML\"><mi>a&lt;b&amp;c</mi></math>");
+    }
+
+    #[test]
+    fn substitution() {
+        use std::collections::{BTreeMap, HashMap};
+        use num_notation::Number;
+        use mono::mono::Mono;
+        use poly::poly::Poly;
+
+        let e: Expr = Expr::parse("3*x^2*y + sin(x)").unwrap();
+        let values: BTreeMap<String, Expr> = BTreeMap::from([("x".to_string(), Expr::parse("2*t + 1").unwrap())]);
+        let s: Expr = e.subs(&values).unwrap();
+        assert!(!s.depends_on("x"));
+        assert!((eval_at(&s, &[("t", 0.5), ("y", 3.0)]) - eval_at(&e, &[("x", 2.0), ("y", 3.0)])).abs() < 1e-12);
+        assert_eq!(Expr::parse("x*y").unwrap().subs(&BTreeMap::from([("y".to_string(), Expr::from(4.0))])).unwrap().to_string(), "x*4");
+
+        let terms: HashMap<String, Expr> = ["x^2*y", "x"].iter()
+            .map(|s| (s.to_string(), Expr::parse(s).unwrap().simplify()))
+            .collect();
+        let p: Poly = Poly::from(terms);
+        let shifted: Poly = p.subs(&values).unwrap();
+        assert_eq!(shifted.to_string(), "4*t^2*y + 4*t*y + 2*t + y + 1");
+        assert!(p.subs(&BTreeMap::from([("x".to_string(), Expr::parse("sin(t)").unwrap())])).is_none());
+
+        let fixed: Poly = p.partial_eval(&BTreeMap::from([("x".to_string(), Number::Decimal(2.0))]));
+        assert_eq!(fixed.to_string(), "4*y + 2");
+
+        // like monomials share a key, also after a division moved them
+        let x: Mono = Mono::from((Number::Decimal(1.0), BTreeMap::from([("x".to_string(), Number::Decimal(1.0))])));
+        let divided: Poly = (p.clone() / x.clone()).unwrap() + Mono::from((Number::Decimal(2.0), BTreeMap::new()));
+        assert_eq!(divided.to_string(), "x*y + 3");
+        assert!((p.clone() / Mono::from((Number::Decimal(0.0), BTreeMap::new()))).is_err());
+        assert_eq!((p * x.clone() - &x * &x).to_string(), "x^3*y");
+    }
+
+    #[test]
+    fn expand_to_poly() {
+        use poly::poly::Poly;
+        use poly::expand::ExpandError;
+
+        let p: Poly = Poly::try_from(&Expr::parse("(x + 1)^3 - sqr(x - y)/2 + -(2*x*y)").unwrap()).unwrap();
+        assert_eq!(p.to_string(), "x^3 + 2.5*x^2 - x*y - 0.5*y^2 + 3*x + 1");
+
+        let cancelled: Poly = Poly::try_from(&Expr::parse("(a - b)*(a + b) - a^2").unwrap()).unwrap();
+        assert_eq!(cancelled.to_string(), "-b^2");
+
+        let err: ExpandError = Poly::try_from(&Expr::parse("x^2 + sin(y)").unwrap()).unwrap_err();
+        assert_eq!(err.node, "sin(y)");
+        assert_eq!(Poly::try_from(&Expr::parse("x/y").unwrap()).unwrap_err().node, "x/y");
+        assert_eq!(Poly::try_from(&Expr::parse("x^0.5").unwrap()).unwrap_err().node, "x^0.5");
+        assert!(Poly::try_from(&Expr::parse("x/(4 - 2*2)").unwrap()).is_err());
+
+        let binomial: Poly = Poly::try_from(&Expr::parse("(x + 1)^13").unwrap()).unwrap();
+        assert_eq!(binomial.sorted_monomials()[6].coefficient().clone(), 1716.0);
+        assert_eq!(Poly::try_from(&Expr::parse("(x + 1)^1e10").unwrap()).unwrap_err().message, "exponent is not a natural number");
+        assert_eq!(Poly::try_from(&Expr::parse("(x + 1)^5000").unwrap()).unwrap_err().message, "exponent exceeds 1024");
+    }
+
+    #[test]
+    fn integration() {
+        use std::collections::BTreeMap;
+        use num_notation::Number;
+        use expr::print::PrintStyle;
+        use mono::mono::Mono;
+        use poly::poly::Poly;
+        use coefficient::rational::Rational;
+
+        // every closed form differentiates back to the integrand
+        for input in ["3*x^2 - 4*x + 1", "x^-2 + sqrt(x)", "a*sin(2*x + 1) - cos(x)/3", "2*x*cos(x^2)", "x*(x^2 + 1)^3", "x^2*sin(3*x)", "sqrt(4*x - 1)", "2^x"] {
+            let e: Expr = Expr::parse(input).unwrap();
+            let primitive: Expr = e.integrate("x");
+            assert!(!primitive.to_string().contains("integrate"), "{} -> {}", input, primitive);
+            for x in [0.3, 0.7, 1.9] {
+                let values: &[(&str, f64)] = &[("x", x), ("a", 1.5)];
+                assert!((eval_at(&primitive.partial_wrt("x"), values) - eval_at(&e, values)).abs() < 1e-9, "{} -> {}", input, primitive);
+            }
+        }
+
+        let open: Expr = Expr::parse("y + sin(x)/x").unwrap().integrate("x");
+        assert_eq!(open.to_string(), "integrate(sin(x)/x, x) + x*y");
+        assert_eq!(open.partial_wrt("x").simplify(), Expr::parse("y + sin(x)/x").unwrap().simplify());
+
+        let area: Expr = Expr::parse("x^2").unwrap().integrate_between("x", Expr::from(0.0), Expr::from(3.0));
+        assert_eq!(area.to_string(), "9");
+        let numeric: Expr = Expr::parse("sin(x)/x").unwrap().integrate_between("x", Expr::from(1.0), Expr::from(2.0));
+        assert_eq!(numeric.to_string(), "integrate(sin(x)/x, x, 1, 2)");
+        assert!((eval_at(&numeric, &[]) - 0.659329906435512).abs() < 1e-9);
+        assert!(!numeric.depends_on("x"));
+        assert_eq!(Expr::from_full_form(&numeric.to_full_form().unwrap()).unwrap(), numeric);
+        assert_eq!(open.to_latex(), "\\int \\frac{\\sin\\left(x\\right)}{x} \\, dx + x y");
+
+        // the printed forms parse back, the bounds are children of their own
+        let compact: String = numeric.pretty(PrintStyle::Compact);
+        assert_eq!(compact, "integrate(sin(x)/x,x,1,2)");
+        assert_eq!(Expr::parse(&compact).unwrap(), numeric);
+        assert!(Expr::parse(&open.to_string()).unwrap().equivalent(&open));
+        assert_eq!(numeric.bounds(), (&Expr::from(1.0), &Expr::from(2.0)));
+        assert_eq!(Expr::parse("integrate(x)").unwrap_err().message, "expected `,`");
+        assert_eq!(Expr::parse("integrate(x, 2)").unwrap_err().column, 14);
+
+        // the variable of an indefinite integral has no value
+        let x_to_t: BTreeMap<String, Expr> = BTreeMap::from([("x".to_string(), Expr::from("t"))]);
+        assert!(open.subs(&x_to_t).is_none());
+        assert!(numeric.subs(&x_to_t).is_some());
+        assert!(eval_at(&open, &[("x", 1.0), ("y", 2.0)]).is_nan());
+        assert!(open.try_eval(&Variables::from([("x".to_string(), Number::Decimal(1.0)), ("y".to_string(), Number::Decimal(2.0))])).is_none());
+        assert!(numeric.try_eval(&Variables::new()).is_some());
+
+        let p: Poly = Poly::try_from(&Expr::parse("6*x^2*y + 2*x").unwrap()).unwrap();
+        assert_eq!(p.integrate("x").unwrap().to_string(), "2*x^3*y + x^2");
+        let reciprocal: Poly = Poly::from(Mono::from((Number::Decimal(1.0), BTreeMap::from([("x".to_string(), Number::Decimal(-1.0))]))));
+        assert!(reciprocal.integrate("x").is_none());
+        assert_eq!(p.derivative("x").unwrap().to_string(), "12*x*y + 2");
+        assert_eq!(p.derivative("z").unwrap().to_string(), "0");
+        assert!(reciprocal.derivative("x").is_none());
+
+        // the factors are exact over the rationals
+        let q: Poly<Rational> = Poly::try_from(&Expr::parse("x^2/3 + y").unwrap()).unwrap().to_rational().unwrap();
+        assert_eq!(q.integrate("x").unwrap().to_string(), "1/9*x^3 + x*y");
+        assert_eq!(q.integrate("x").unwrap().derivative("x").unwrap(), q);
+    }
+
+    #[test]
+    fn series_expansion() {
+        use expr::series::Series;
+
+        let sin: Series = Expr::parse("sin(x^2)").unwrap().series("x", 0.0, 6).unwrap();
+        assert_eq!(sin.valuation(), Some(2));
+        assert!((sin.coefficient(6) + 1.0 / 6.0).abs() < 1e-12);
+        assert_eq!(sin.coefficient(4), 0.0);
+        assert_eq!(sin.order, 7);
+
+        let cot: Series = Expr::parse("cos(x)/sin(x)").unwrap().series("x", 0.0, 3).unwrap();
+        assert_eq!(cot.valuation(), Some(-1));
+        assert!((cot.coefficient(-1) - 1.0).abs() < 1e-12);
+        assert!((cot.coefficient(1) + 1.0 / 3.0).abs() < 1e-12);
+        assert!((cot.coefficient(3) + 1.0 / 45.0).abs() < 1e-12);
+
+        let pole: Series = Expr::parse("1/(x^2 - 1)").unwrap().series("x", 1.0, 1).unwrap();
+        assert_eq!(pole.to_string(), "0.5*(x - 1)^(-1) - 0.25 + 0.125*(x - 1) + O((x - 1)^2)");
+        assert!((eval_at(&pole.to_expr(), &[("x", 1.01)]) - 1.0 / (1.01f64.powi(2) - 1.0)).abs() < 1e-5);
+
+        let cancel: Series = Expr::parse("(1 - cos(x))/x^2").unwrap().series("x", 0.0, 2).unwrap();
+        assert_eq!(cancel.to_string(), "0.5 - 0.041666666666666664*x^2 + O(x^3)");
+        assert_eq!(Expr::parse("sqrt(1 + x)").unwrap().series("x", 0.0, 2).unwrap().to_string(), "1 + 0.5*x - 0.125*x^2 + O(x^3)");
+
+        // small coefficients are kept, only cancellation counts as zero
+        let long: Series = Expr::parse("sin(x)").unwrap().series("x", 0.0, 25).unwrap();
+        let factorial = |n: i32| -> f64 { (1..=n).map(f64::from).product() };
+        assert!((long.coefficient(21) * factorial(21) - 1.0).abs() < 1e-9);
+        assert!((long.coefficient(23) * factorial(23) + 1.0).abs() < 1e-9);
+        assert_eq!(long.coefficient(24), 0.0);
+        assert_eq!(Expr::parse("1e-13*x + x^2").unwrap().series("x", 0.0, 2).unwrap().coefficient(1), 1e-13);
+        assert!(Expr::parse("sqrt(x)").unwrap().series("x", 0.0, 2).is_err());
+    }
+
+    #[test]
+    fn limits() {
+        use expr::limit::*;
+
+        let at = |input: &str, point: LimitPoint, direction: Direction| -> Limit {
+            return Expr::parse(input).unwrap().limit("x", point, direction).unwrap();
+        };
+        let zero: LimitPoint = LimitPoint::Finite(0.0);
+
+        assert_eq!(at("sin(x)/x", zero, Direction::Both), Limit::Finite(1.0));
+        assert_eq!(at("(x^2 - 1)/(x - 1)", LimitPoint::Finite(1.0), Direction::Both), Limit::Finite(2.0));
+        assert!(matches!(at("(1 - cos(x))/x^2", zero, Direction::Both), Limit::Finite(v) if (v - 0.5).abs() < 1e-12));
+        assert_eq!(at("1/x", zero, Direction::Left), Limit::NegInfinity);
+        assert_eq!(at("1/x", zero, Direction::Right), Limit::PosInfinity);
+        assert_eq!(at("1/x", zero, Direction::Both), Limit::DoesNotExist);
+        assert_eq!(at("-1/x^2", zero, Direction::Both), Limit::NegInfinity);
+
+        // no series at branch points and essential singularities
+        assert_eq!(at("sqrt(x)/x", zero, Direction::Right), Limit::PosInfinity);
+        assert_eq!(at("sqrt(x)", zero, Direction::Left), Limit::DoesNotExist);
+        assert_eq!(at("sin(1/x)", zero, Direction::Right), Limit::DoesNotExist);
+        assert_eq!(at("x*sin(1/x)", zero, Direction::Both), Limit::Finite(0.0));
+
+        assert_eq!(at("(2*x^2 + 1)/(x^2 - 3)", LimitPoint::PosInfinity, Direction::Both), Limit::Finite(2.0));
+        assert_eq!(at("sin(x)/x", LimitPoint::PosInfinity, Direction::Both), Limit::Finite(0.0));
+        assert_eq!(at("-x^3", LimitPoint::NegInfinity, Direction::Both), Limit::PosInfinity);
+        assert_eq!(at("cos(x)", LimitPoint::PosInfinity, Direction::Both), Limit::DoesNotExist);
+
+        // zero tests are exact, not against an absolute tolerance
+        assert_eq!(at("1e-13/x", zero, Direction::Right), Limit::PosInfinity);
+        assert_eq!(at("x^x", zero, Direction::Right), Limit::Finite(1.0));
+
+        // free variables, integrals and unresolved rewrites are errors, not panics
+        let limit = |input: &str, point: LimitPoint| -> Result<Limit, String> {
+            return Expr::parse(input).unwrap().limit("x", point, Direction::Both);
+        };
+        assert!(limit("a*x", LimitPoint::Finite(2.0)).is_err());
+        assert!(limit("integrate(sin(t)/t, t, 1, x)", LimitPoint::Finite(2.0)).is_err());
+        assert!(limit("x/sqrt(x^2+1)", LimitPoint::PosInfinity).is_err());
+        assert!(limit("sqrt(x^2+x)-x", LimitPoint::PosInfinity).is_err());
+    }
+
+    #[test]
+    fn compiled_eval() {
+        use std::collections::BTreeMap;
+        use num_notation::Number;
+
+        let inputs = [
+            "3*x^2*y - 2*y/x + 7",
+            "sin(x)*cos(y) - sqrt(x^2 + y^2)",
+            "(x - y)^3/(1 + x*y)",
+            "asin(x/4) + acos(y/4) - 2^x",
+            "-(x + 1)*(y - 2)*(x*y + 3) + x^0.5",
+        ];
+        for input in inputs {
+            let e: Expr = Expr::parse(input).unwrap();
+            let compiled = e.compile(&["x", "y"]).unwrap();
+            for (x, y) in [(0.5, 1.5), (1.0, -2.0), (3.25, 0.75), (2.0, 3.0)] {
+                let values: BTreeMap<String, Number> = BTreeMap::from([
+                    (String::from("x"), Number::Decimal(x)),
+                    (String::from("y"), Number::Decimal(y)),
+                ]);
+                let expected: f64 = f64::from(e.Eval(&values));
+                let actual: f64 = compiled.eval(&[x, y]);
+                assert!(expected.to_bits() == actual.to_bits() || (expected.is_nan() && actual.is_nan()), "{}: {} != {}", input, expected, actual);
+            }
+        }
+
+        // slots follow the order given to compile, not the alphabetical one
+        let e: Expr = Expr::parse("x - y").unwrap();
+        assert_eq!(e.compile(&["y", "x"]).unwrap().eval(&[1.0, 5.0]), 4.0);
+        let mut registers: Vec<f64> = vec![0.0; e.compile(&["x", "y"]).unwrap().registers()];
+        assert_eq!(e.compile(&["x", "y"]).unwrap().eval_with(&[1.0, 5.0], &mut registers), -4.0);
+
+        // integrals and unbound variables are errors, not panics
+        let definite: Expr = Expr::parse("x + integrate(sin(t)/t, t, 1, x)").unwrap();
+        let error = definite.compile(&["x"]).unwrap_err();
+        assert_eq!(error.message, "integrals cannot be compiled, use Eval");
+        assert_eq!(e.compile(&["x"]).unwrap_err().message, "variable y is not bound to a slot");
+    }
+
+    #[test]
+    fn batch_eval() {
+        use std::collections::BTreeMap;
+        use ndarray::{array, Array1, Array2};
+        use poly::poly::Poly;
+        use num_notation::Number;
+
+        let xs: Array1<f64> = array![0.5, 1.0, -1.5, 2.0, 3.25];
+        let ys: Array1<f64> = array![1.5, -2.0, 0.25, 3.0, -0.75];
+        let at = |e: &Expr, i: usize| -> f64 {
+            let values: BTreeMap<String, Number> = BTreeMap::from([
+                (String::from("x"), Number::Decimal(xs[i])),
+                (String::from("y"), Number::Decimal(ys[i])),
+            ]);
+            return f64::from(e.Eval(&values));
+        };
+
+        let e: Expr = Expr::parse("sin(x)*cos(y) - (x - y)^2/(1 + x^2) + 3*x*y^2").unwrap();
+        let batch: Array1<f64> = e.eval_array(&["x", "y"], &[xs.view(), ys.view()]).unwrap();
+        for i in 0..xs.len() {
+            assert_eq!(batch[i], at(&e, i));
+        }
+
+        // rows are sample points, columns follow the variable list
+        let points: Array2<f64> = Array2::from_shape_fn((xs.len(), 2), |(i, j)| if j == 0 { ys[i] } else { xs[i] });
+        assert_eq!(e.eval_points(&["y", "x"], points.view()).unwrap(), batch);
+        assert_eq!(Expr::from(2.0).eval_points(&[], Array2::zeros((3, 0)).view()).unwrap(), array![2.0, 2.0, 2.0]);
+
+        let e: Expr = Expr::parse("2*x^3*y - x^2*y^2 + 4*x - 5*y + 7").unwrap();
+        let p: Poly = Poly::try_from(&e).unwrap();
+        let horner: Array1<f64> = p.eval_array(&["x", "y"], &[xs.view(), ys.view()]).unwrap();
+        for i in 0..xs.len() {
+            assert!((horner[i] - at(&e, i)).abs() < 1e-9);
+        }
+        assert_eq!(p.eval_points(&["x", "y"], Array2::from_shape_vec((1, 2), vec![1.0, 2.0]).unwrap().view()).unwrap(), array![1.0]);
+
+        // a variable without a column is an error, not a panic
+        assert_eq!(e.eval_array(&["x"], &[xs.view()]).unwrap_err().message, "no column for variable y");
+        assert_eq!(p.eval_array(&["y"], &[ys.view()]).unwrap_err().message, "no column for variable x");
+        assert!(e.eval_array(&["x", "y"], &[xs.view()]).is_err());
+        assert!(p.eval_points(&["x", "y"], Array2::zeros((2, 3)).view()).is_err());
+    }
+
+    #[test]
+    fn gradient() {
+        use std::collections::BTreeMap;
+        use num_notation::Number;
+
+        let values: BTreeMap<String, Number> = BTreeMap::from([
+            (String::from("x"), Number::Decimal(0.7)),
+            (String::from("y"), Number::Decimal(1.3)),
+            (String::from("z"), Number::Decimal(2.1)),
+        ]);
+        let inputs = [
+            "3*x^2*y^0.5*z^-1.5 - 2*y/x",
+            "sin(x*y)*cos(z) + sqrt(x^2 + y^2 + z^2)",
+            "asin(x/2)*acos(y/3) - (x - z)^2",
+            "2^(x*y) - (x + y)^3/(1 + z)",
+        ];
+        let integral: Expr = Expr::parse("x*y*t^2").unwrap().Definite("t", Expr::from(0.0), Expr::from("z"));
+        let exprs = inputs.iter().map(|input| Expr::parse(input).unwrap()).chain([integral]);
+        for e in exprs {
+            let input: String = e.to_string();
+            let (value, gradient) = e.eval_with_gradient(&values);
+            assert!((value - f64::from(e.Eval(&values))).abs() < 1e-12, "{}", input);
+            assert_eq!(gradient.keys().cloned().collect::<Vec<String>>(), e.free_variables().into_iter().collect::<Vec<String>>());
+            for (v, d) in gradient.iter() {
+                let expected: f64 = f64::from(e.partial_wrt(v).Eval(&values));
+                assert!((d - expected).abs() < 1e-9 * expected.abs().max(1.0), "d/d{} of {}: {} != {}", v, input, d, expected);
+            }
+        }
+
+        // variable base and exponent, beyond partial_wrt, checked by central differences
+        let e: Expr = Expr::parse("z^(x*y)").unwrap();
+        let (_, gradient) = e.eval_with_gradient(&values);
+        for (v, d) in gradient.iter() {
+            let shifted = |h: f64| -> f64 {
+                let mut point: BTreeMap<String, Number> = values.clone();
+                point.insert(v.clone(), Number::Decimal(f64::from(values[v].clone()) + h));
+                return f64::from(e.Eval(&point));
+            };
+            assert!((d - (shifted(1e-6) - shifted(-1e-6)) / 2e-6).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn reverse_gradient() {
+        use std::collections::BTreeMap;
+        use std::sync::Arc;
+        use num_notation::Number;
+
+        let values: BTreeMap<String, Number> = BTreeMap::from([
+            (String::from("x"), Number::Decimal(0.7)),
+            (String::from("y"), Number::Decimal(1.3)),
+            (String::from("z"), Number::Decimal(2.1)),
+        ]);
+        let inputs = [
+            "3*x^2*y^0.5*z^-1.5 - 2*y/x",
+            "sin(x*y)*cos(z) + sqrt(x^2 + y^2 + z^2)",
+            "asin(x/2)*acos(y/3) - (x - z)^2",
+            "z^(x*y) - (x + y)^3/(1 + z)",
+        ];
+        let integral: Expr = Expr::parse("x*y*t^2").unwrap().Definite("t", Expr::from(0.0), Expr::from("z"));
+        for e in inputs.iter().map(|input| Expr::parse(input).unwrap()).chain([integral]) {
+            let (value, gradient) = e.eval_with_gradient_reverse(&values).unwrap();
+            let (expected, forward) = e.eval_with_gradient(&values);
+            assert!((value - expected).abs() < 1e-12, "{}", e);
+            assert_eq!(gradient.keys().collect::<Vec<_>>(), forward.keys().collect::<Vec<_>>());
+            for (v, d) in gradient.iter() {
+                assert!((d - forward[v]).abs() < 1e-9 * forward[v].abs().max(1.0), "d/d{} of {}: {} != {}", v, e, d, forward[v]);
+            }
+        }
+
+        // (u + u) * u with every u the same Arc: u is recorded once and collects three adjoints
+        let u: Arc<Expr> = Arc::new(Expr::parse("sin(x*y) + x^2").unwrap());
+        let mut sum: Expr = Expr::from(0.0).Plus(Expr::from(0.0));
+        sum.a = Some(u.clone());
+        sum.b = Some(u.clone());
+        let mut product: Expr = Expr::from(0.0).Times(Expr::from(0.0));
+        product.a = Some(Arc::new(sum));
+        product.b = Some(u.clone());
+        let tape = product.tape(&["x", "y"]).unwrap();
+        assert_eq!(tape.len(), u.tape(&["x", "y"]).unwrap().len() + 2);
+        let (value, gradient) = tape.gradient(&[0.7, 1.3]);
+        let (expected, forward) = product.eval_with_gradient(&values);
+        assert!((value - expected).abs() < 1e-12);
+        assert!((gradient[0] - forward["x"]).abs() < 1e-12 && (gradient[1] - forward["y"]).abs() < 1e-12);
+
+        // unbound and missing variables are errors, not panics
+        assert_eq!(u.tape(&["x"]).unwrap_err().message, "variable y is not bound to a slot");
+        let partial: BTreeMap<String, Number> = BTreeMap::from([(String::from("x"), Number::Decimal(0.7))]);
+        assert_eq!(u.eval_with_gradient_reverse(&partial).unwrap_err().message, "no value for variable y");
+    }
+
+    #[test]
+    fn interval_eval() {
+        use std::collections::BTreeMap;
+        use std::f64::consts::PI;
+        use num_notation::Number;
+        use expr::interval::Interval;
+
+        let at = |input: &str, x: Interval| -> Interval {
+            return Expr::parse(input).unwrap().eval_interval(&BTreeMap::from([(String::from("x"), x)]));
+        };
+        let sqrt: Interval = at("sqrt(x)", Interval::new(-4.0, 9.0));
+        assert!(sqrt.lo == 0.0 && sqrt.contains(3.0) && sqrt.hi < 3.0 + 1e-12);
+        assert!(at("sqrt(x)", Interval::new(-2.0, -1.0)).is_empty());
+        let asin: Interval = at("asin(x)", Interval::new(0.5, 3.0));
+        assert!(asin.contains(PI / 6.0) && asin.hi == PI / 2.0);
+        assert!(at("acos(x)", Interval::new(2.0, 3.0)).is_empty());
+        assert_eq!(at("sin(x)", Interval::new(0.0, 7.0)), Interval::new(-1.0, 1.0));
+        assert_eq!(at("cos(x)", Interval::new(-0.1, 0.1)).hi, 1.0);
+        let sin: Interval = at("sin(x)", Interval::new(0.1, 0.2));
+        assert!(sin.contains(0.1_f64.sin()) && sin.contains(0.2_f64.sin()) && sin.width() < 0.1);
+        let half: Interval = at("1/x", Interval::new(0.0, 2.0));
+        assert!(half.contains(0.5) && half.lo > 0.49 && half.hi == f64::INFINITY);
+        assert_eq!(at("1/x", Interval::new(-1.0, 2.0)), Interval::entire());
+        assert!(at("1/x", Interval::point(0.0)).is_empty());
+
+        // every sampled value lies in the enclosure
+        let inputs = [
+            "x^2*y - 3*x/y + sqrt(x + y)",
+            "sin(x*y)*cos(x - y) + x^3",
+            "(x - y)^2/(1 + x^2) - 2^(x*y)",
+            "asin(x/4) + acos(y/4) + x*y^-2",
+        ];
+        let box_: BTreeMap<String, Interval> = BTreeMap::from([
+            (String::from("x"), Interval::new(0.5, 2.5)),
+            (String::from("y"), Interval::new(1.0, 1.75)),
+        ]);
+        for input in inputs {
+            let e: Expr = Expr::parse(input).unwrap();
+            let enclosure: Interval = e.eval_interval(&box_);
+            for i in 0..=10 {
+                for j in 0..=10 {
+                    let (x, y) = (0.5 + 0.2 * i as f64, 1.0 + 0.075 * j as f64);
+                    let values: BTreeMap<String, Number> = BTreeMap::from([
+                        (String::from("x"), Number::Decimal(x)),
+                        (String::from("y"), Number::Decimal(y)),
+                    ]);
+                    let value: f64 = f64::from(e.Eval(&values));
+                    assert!(enclosure.contains(value), "{} at ({}, {}) = {} outside {}", input, x, y, value, enclosure);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn complex_eval() {
+        use std::collections::BTreeMap;
+        use std::f64::consts::PI;
+        use num_complex::Complex;
+        use num_notation::Number;
+
+        let at = |input: &str, x: Complex<f64>| -> Complex<f64> {
+            return Expr::parse(input).unwrap().eval_complex(&BTreeMap::from([(String::from("x"), x)]));
+        };
+        let close = |a: Complex<f64>, b: Complex<f64>| -> bool { (a - b).norm() < 1e-9 };
+
+        assert!(close(at("sqrt(x)", Complex::new(-1.0, 0.0)), Complex::new(0.0, 1.0)));
+        assert!(close(at("x^0.5", Complex::new(-4.0, 0.0)), Complex::new(0.0, 2.0)));
+        assert!(close(at("asin(x)", Complex::new(2.0, 0.0)), Complex::new(PI / 2.0, -(2.0 + 3.0_f64.sqrt()).ln())));
+        assert!(close(at("acos(x)", Complex::new(2.0, 0.0)), Complex::new(0.0, (2.0 + 3.0_f64.sqrt()).ln())));
+        // the roots 1 +- 2i of a real quadratic substituted back
+        assert!(close(at("x^2 - 2*x + 5", Complex::new(1.0, 2.0)), Complex::new(0.0, 0.0)));
+        assert!(close(at("x^2 - 2*x + 5", Complex::new(1.0, -2.0)), Complex::new(0.0, 0.0)));
+        assert!(close(at("2^x", Complex::new(0.0, PI / 2.0_f64.ln())), Complex::new(-1.0, 0.0)));
+
+        // agrees with Eval wherever Eval is real
+        let e: Expr = Expr::parse("sin(x*y)*cos(x - y) + sqrt(x^2 + y^2)/(x - 3) + x^-2*y^3").unwrap();
+        let real: BTreeMap<String, Number> = BTreeMap::from([
+            (String::from("x"), Number::Decimal(0.7)),
+            (String::from("y"), Number::Decimal(-1.3)),
+        ]);
+        let complex: BTreeMap<String, Complex<f64>> = BTreeMap::from([
+            (String::from("x"), Complex::new(0.7, 0.0)),
+            (String::from("y"), Complex::new(-1.3, 0.0)),
+        ]);
+        assert!(close(e.eval_complex(&complex), Complex::new(f64::from(e.Eval(&real)), 0.0)));
+
+        let integral: Expr = Expr::parse("t^2").unwrap().Definite("t", Expr::from(0.0), Expr::from("x"));
+        let value: Complex<f64> = integral.eval_complex(&BTreeMap::from([(String::from("x"), Complex::new(0.0, 1.0))]));
+        assert!(close(value, Complex::new(0.0, -1.0 / 3.0)));
+    }
+
+    #[test]
+    fn rational_coefficients() {
+        use std::collections::HashSet;
+        use coefficient::rational::Rational;
+        use poly::poly::Poly;
+        use ring::ring::Ring;
+
+        assert_eq!(Rational::from_f64(0.1), Some(Rational::new(1, 10)));
+        assert_eq!(Rational::from_f64(-2.5), Some(Rational::new(-5, 2)));
+        assert_eq!(Rational::from_f64(f64::NAN), None);
+        assert_eq!(Rational::new(1, 3) + Rational::new(1, 6), Rational::new(1, 2));
+        assert_eq!((Rational::new(2, 3) * Rational::new(3, 4) - Rational::new(1, 2)).to_string(), "0");
+
+        let exact = |input: &str| -> Poly<Rational> {
+            return Poly::try_from(&Expr::parse(input).unwrap()).unwrap().to_rational().unwrap();
+        };
+        let f: Poly<Rational> = exact("x^2 - y/3");
+        let g: Poly<Rational> = exact("x*y - 1");
+        assert_eq!(f.to_string(), "x^2 - 1/3*y");
+        assert_eq!(Ring::s_poly(f.clone(), g.clone()).to_string(), "-1/3*y^2 + x");
+
+        // monomials print their exact coefficient, products stay exact
+        let third: Mono<Rational> = f.sorted_monomials()[1].clone();
+        assert_eq!(third.to_string(), "-1/3*y");
+        assert_eq!((&third * &third).to_string(), "1/9*y^2");
+
+        // 0.1 + 0.2 - 0.3 is not zero in floats, the reductions below are exact
+        let (q, r) = (exact("x + 0.1") * exact("x + 0.2") - exact("0.3*x")) / exact("x + 0.1");
+        assert_eq!(q.to_string(), "x - 1/10");
+        assert_eq!(r.to_string(), "3/100");
+        let (_, mut r) = (exact("x^3 - x/7") * exact("3*y + 1")) / exact("3*x*y + x");
+        assert!(r.zero());
+
+        let basis: HashSet<Poly<Rational>> = Ring::reduced_grobner_basis(HashSet::from([f.clone(), g.clone()]));
+        for p in [f, g] {
+            let (_, mut r) = p / basis.iter().cloned().collect::<Vec<_>>();
+            assert!(r.zero());
+        }
+    }
+
+    #[test]
+    fn coefficient_domains() {
+        use std::collections::{BTreeMap, HashSet};
+        use std::fmt::{Display, Formatter};
+        use std::ops::{Add, Mul, Neg, Sub};
+        use num_notation::Number;
+        use coefficient::coefficient::Coefficient;
+        use coefficient::rational::Rational;
+        use poly::poly::Poly;
+        use ring::ring::Ring;
+
+        let x = |n: f64| -> BTreeMap<String, Number> { BTreeMap::from([(String::from("x"), Number::Decimal(n))]) };
+
+        assert_eq!(Rational::new(-2, 3).inverse(), Some(Rational::new(-3, 2)));
+        assert_eq!(Rational::zero().inverse(), None);
+        assert_eq!(4.0_f64.inverse(), Some(0.25));
+        assert_eq!(Ring::<Rational>::default().domain(), "QQ");
+        assert_eq!(Ring::<f64>::from("x").domain(), "RDF");
+        assert_eq!(Ring::<Number>::default().domain(), "RR");
+
+        // x^2 - 1 = (x + 1)(x - 1) over f64
+        let p: Poly<f64> = Poly::from(Mono::from((1.0, x(2.0)))) - Mono::from((1.0, x(0.0)));
+        let (q, mut r) = p / (Poly::from(Mono::from((1.0, x(1.0)))) + Mono::from((1.0, x(0.0))));
+        assert_eq!(q.to_string(), "x - 1");
+        assert!(r.zero());
+
+        // the integers, where only 1 and -1 are units
+        #[derive(Clone, Debug, PartialEq)]
+        struct Z(i64);
+        impl Display for Z {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.0) }
+        }
+        impl Add for Z { type Output = Z; fn add(self, o: Z) -> Z { Z(self.0 + o.0) } }
+        impl Sub for Z { type Output = Z; fn sub(self, o: Z) -> Z { Z(self.0 - o.0) } }
+        impl Mul for Z { type Output = Z; fn mul(self, o: Z) -> Z { Z(self.0 * o.0) } }
+        impl Neg for Z { type Output = Z; fn neg(self) -> Z { Z(-self.0) } }
+        impl Coefficient for Z {
+            fn zero() -> Self { Z(0) }
+            fn one() -> Self { Z(1) }
+            fn is_zero(&self) -> bool { self.0 == 0 }
+            fn inverse(&self) -> Option<Self> { if self.0.abs() == 1 { Some(self.clone()) } else { None } }
+            fn to_number(&self) -> Number { Number::Decimal(self.0 as f64) }
+            fn domain() -> String { String::from("ZZ") }
+            fn divide(&self, other: &Self) -> Option<Self> {
+                if other.0 != 0 && self.0 % other.0 == 0 { Some(Z(self.0 / other.0)) } else { None }
+            }
+        }
+
+        // 6x^2 + 3x = 3x(2x + 1), while 2x does not divide 3x over ZZ
+        let p: Poly<Z> = Poly::from(Mono::from((Z(6), x(2.0)))) + Mono::from((Z(3), x(1.0)));
+        let (q, r) = p.clone() / Poly::from(Mono::from((Z(3), x(1.0))));
+        assert_eq!((q.to_string(), r.to_string()), (String::from("2*x + 1"), String::from("0")));
+        let (q, r) = p / Poly::from(Mono::from((Z(2), x(1.0))));
+        assert_eq!((q.to_string(), r.to_string()), (String::from("3*x"), String::from("3*x")));
+        let ring: Ring<Z> = Ring::from(HashSet::from([String::from("x")]));
+        assert_eq!(ring.domain(), "ZZ");
+    }
+
+    #[test]
+    fn modular_grobner_basis() {
+        use std::collections::{BTreeMap, HashSet};
+        use num_bigint::BigInt;
+        use num_notation::Number;
+        use coefficient::coefficient::Coefficient;
+        use coefficient::modular::*;
+        use coefficient::rational::Rational;
+        use poly::poly::Poly;
+        use ring::ring::Ring;
+
+        assert_eq!(GF::<7>::new(-1), GF::<7>::new(6));
+        assert_eq!(GF::<7>::new(3).inverse(), Some(GF::<7>::new(5)));
+        assert_eq!(GF::<7>::zero().inverse(), None);
+        assert_eq!(GF::<7>::new(3).pow(6), GF::<7>::one());
+        assert_eq!(GF::<7>::from_rational(&Rational::new(1, 3)), Some(GF::<7>::new(5)));
+        assert_eq!(GF::<7>::from_rational(&Rational::new(1, 14)), None);
+        assert_eq!(Ring::<GF<7>>::default().domain(), "GF(7)");
+        assert_eq!(rational_reconstruction(&BigInt::from(34), &BigInt::from(101)), Some(Rational::new(1, 3)));
+        assert_eq!(rational_reconstruction(&BigInt::from(-5), &BigInt::from(101)), Some(Rational::integer(-5)));
+
+        let exact = |input: &str| -> Poly<Rational> {
+            return Poly::try_from(&Expr::parse(input).unwrap()).unwrap().to_rational().unwrap();
+        };
+        let y: BTreeMap<String, Number> = BTreeMap::from([(String::from("y"), Number::Decimal(1.0))]);
+        let big: Rational = Rational::new(123456789012345678901_i128, 7);
+        let f: Poly<Rational> = exact("x^2") - Mono::from((big, y));
+        let g: Poly<Rational> = exact("x*y - 1");
+        let input: HashSet<Poly<Rational>> = HashSet::from([f, g]);
+
+        // the basis mod p is the image of the basis over Q
+        let direct: HashSet<Poly<Rational>> = Ring::reduced_grobner_basis(input.clone());
+        let mod_p: HashSet<Poly<GF<101>>> = Ring::reduced_grobner_basis(input.iter().map(|p| p.reduce_mod::<101>().unwrap()).collect());
+        assert_eq!(mod_p, direct.iter().map(|p| p.reduce_mod::<101>().unwrap()).collect::<HashSet<_>>());
+
+        // verified by the lifted cofactors, `try_` never computes a basis over Q
+        let modular: HashSet<Poly<Rational>> = Ring::try_modular_reduced_grobner_basis(&input).unwrap();
+        assert_eq!(modular, direct);
+        assert!(modular.iter().all(|p| p.clone().leading_coefficient() == Rational::integer(1)));
+        assert_eq!(Ring::modular_reduced_grobner_basis(input), direct);
+        // cofactors of higher degree, lifted alongside the basis
+        let system: HashSet<Poly<Rational>> = ["x^2 + y^2 + z^2 - 1", "x*y*z - 1/2", "x - y + z/3"].iter().map(|s| exact(s)).collect();
+        assert_eq!(Ring::try_modular_reduced_grobner_basis(&system).unwrap(), Ring::reduced_grobner_basis(system));
+
+        // both primes divide the leading coefficient, the images mod them are the unit ideal
+        let x: BTreeMap<String, Number> = BTreeMap::from([(String::from("x"), Number::Decimal(1.0))]);
+        let unlucky: Rational = Rational::integer(BigInt::from(PRIMES[0]) * BigInt::from(PRIMES[1]));
+        let input: HashSet<Poly<Rational>> = HashSet::from([exact("-1") + Mono::from((unlucky, x.clone())), exact("y - 2")]);
+        let modular: HashSet<Poly<Rational>> = Ring::try_modular_reduced_grobner_basis(&input).unwrap();
+        assert_eq!(modular, Ring::reduced_grobner_basis(input));
+        assert!(modular.iter().all(|p| p.to_string() != "1"));
+
+        // too large to reconstruct from the sixteen primes, computed over Q instead
+        let sevens: Rational = Rational::integer("7".repeat(160).parse::<BigInt>().unwrap());
+        let input: HashSet<Poly<Rational>> = HashSet::from([exact("x") - Mono::from((sevens, BTreeMap::new())), exact("y - 2")]);
+        assert!(Ring::try_modular_reduced_grobner_basis(&input).is_none());
+        let modular: HashSet<Poly<Rational>> = Ring::modular_reduced_grobner_basis(input.clone());
+        assert_eq!(modular, input);
+    }
+
+    #[test]
+    fn monomial_orders() {
+        use std::cmp::Ordering;
+        use std::collections::{BTreeMap, HashSet};
+        use std::sync::Arc;
+        use num_notation::Number;
+        use coefficient::rational::Rational;
+        use mono::order::*;
+        use poly::poly::Poly;
+        use ring::ring::Ring;
+
+        let m = |input: &str| -> BTreeMap<String, Number> {
+            let p: Poly = Poly::try_from(&Expr::parse(input).unwrap()).unwrap();
+            return p[0].variables().clone();
+        };
+        let (a, b) = (m("x^2*z^2"), m("x*y^2*z"));
+        assert_eq!(Lex.compare(&a, &b), Ordering::Greater);
+        assert_eq!(GrLex.compare(&a, &b), Ordering::Greater);
+        assert_eq!(GRevLex.compare(&a, &b), Ordering::Less);
+        assert_eq!(GRevLex.compare(&m("x^3"), &m("y^2")), Ordering::Greater);
+        assert_eq!(Lex.compare(&m("y^5"), &m("x")), Ordering::Less);
+        assert_eq!(Weight::new(&[("x", 1.0), ("y", 3.0)]).compare(&m("x^2"), &m("y")), Ordering::Less);
+        assert_eq!(Block::elimination(&["t"]).compare(&m("t"), &m("x^5*y")), Ordering::Greater);
+        assert_eq!(Block::elimination(&["t"]).compare(&m("t*y"), &m("t*x")), Ordering::Less);
+
+        // a user order: reverse lex, y > x
+        #[derive(Debug)]
+        struct ReverseLex;
+        impl MonomialOrder for ReverseLex {
+            fn compare(&self, a: &BTreeMap<String, Number>, b: &BTreeMap<String, Number>) -> Ordering {
+                let (va, vb) = exponent_vectors(a, b);
+                let first: Option<(&f64, &f64)> = va.iter().zip(vb.iter()).rev().find(|(x, y)| x != y);
+                return first.map_or(Ordering::Equal, |(x, y)| x.total_cmp(y));
+            }
+        }
+
+        let p: Poly = Poly::try_from(&Expr::parse("x^3 + x*y^3 + y^2 + x").unwrap()).unwrap();
+        assert_eq!(p.to_string(), "x*y^3 + x^3 + y^2 + x");
+        assert_eq!(p.clone().with_order(Arc::new(Lex)).to_string(), "x^3 + x*y^3 + x + y^2");
+        assert_eq!(p.clone().with_order(Arc::new(ReverseLex)).to_string(), "x*y^3 + y^2 + x^3 + x");
+        let mut q: Poly = p.with_order(Arc::new(Lex));
+        assert_eq!(q.leading_term().to_string(), "x^3");
+
+        // eliminating t from x = t^2, y = t^3 leaves the cusp
+        let exact = |input: &str| -> Poly<Rational> {
+            return Poly::try_from(&Expr::parse(input).unwrap()).unwrap().to_rational().unwrap();
+        };
+        let ring: Ring<Rational> = Ring::from(HashSet::from([String::from("t"), String::from("x"), String::from("y")]))
+            .with_order(Arc::new(Block::elimination(&["t"])));
+        let basis: HashSet<Poly<Rational>> = ring.basis(HashSet::from([exact("x - t^2"), exact("y - t^3")]));
+        let eliminated: Vec<Poly<Rational>> = basis.into_iter().filter(|p| p.monomials().values().all(|m| !m.variables().contains_key("t"))).collect();
+        assert_eq!(eliminated, vec![exact("x^3 - y^2")]);
+    }
+
+    #[test]
+    fn buchberger() {
+        use std::collections::HashSet;
+        use coefficient::rational::Rational;
+        use poly::poly::Poly;
+        use ring::buchberger::*;
+        use ring::ring::Ring;
+
+        let exact = |input: &str| -> Poly<Rational> {
+            return Poly::try_from(&Expr::parse(input).unwrap()).unwrap().to_rational().unwrap();
+        };
+        let f: Poly<Rational> = exact("x^3 - 2*x*y");
+        let g: Poly<Rational> = exact("x^2*y - 2*y^2 + x");
+
+        let (normal, stats) = Ring::buchberger(HashSet::from([f.clone(), g.clone()]), Selection::Normal);
+        let printed: Vec<String> = normal.iter().map(|p| p.to_string()).collect();
+        assert_eq!(printed, vec!["y^2 - 1/2*x", "x*y", "x^2"]);
+        assert!(stats.reductions > 0);
+        assert_eq!(stats.pairs, stats.reductions + stats.product_criterion + stats.chain_criterion);
+
+        let (sugar, stats) = Ring::buchberger(HashSet::from([f.clone(), g.clone()]), Selection::Sugar);
+        assert_eq!(sugar, normal);
+        assert_eq!(stats.pairs, stats.reductions + stats.product_criterion + stats.chain_criterion);
+
+        // the reduced basis depends only on the ideal
+        let (other, _) = Ring::buchberger(HashSet::from([f.clone() + g.clone(), g.clone(), f.clone() * exact("y + 3")]), Selection::Normal);
+        assert_eq!(other, normal);
+        assert_eq!(Ring::reduced_grobner_basis(HashSet::from([f, g])), normal.into_iter().collect());
+
+        // coprime leading monomials: the one pair is dropped without a reduction
+        let (basis, stats) = Ring::buchberger(HashSet::from([exact("2*x^2 - 1"), exact("y^3 + y")]), Selection::Normal);
+        assert_eq!(basis.iter().map(|p| p.to_string()).collect::<Vec<_>>(), vec!["x^2 - 1/2", "y^3 + y"]);
+        assert_eq!((stats.pairs, stats.product_criterion, stats.reductions), (1, 1, 0));
+    }
+
+    #[test]
+    fn f4_matches_buchberger() {
+        use std::collections::HashSet;
+        use std::sync::Arc;
+        use coefficient::modular::GF;
+        use coefficient::rational::Rational;
+        use mono::order::*;
+        use poly::poly::Poly;
+        use ring::buchberger::Selection;
+        use ring::ring::Ring;
+
+        let ideals: [&[&str]; 4] = [
+            &["x^3 - 2*x*y", "x^2*y - 2*y^2 + x"],
+            &["x + y + z", "x*y + y*z + z*x", "x*y*z - 1"],
+            &["x + 2*y + 2*z - 1", "x^2 + 2*y^2 + 2*z^2 - x", "2*x*y + 2*y*z - y"],
+            &["x - t^2", "y - t^3", "x*y - t"],
+        ];
+        let orders: [Order; 3] = [Arc::new(GrLex), Arc::new(GRevLex), Arc::new(Lex)];
+        for generators in ideals.iter() {
+            for order in orders.iter() {
+                let rational: HashSet<Poly<Rational>> = generators.iter()
+                    .map(|g| Poly::try_from(&Expr::parse(g).unwrap()).unwrap().to_rational().unwrap().with_order(order.clone()))
+                    .collect();
+                let modular: HashSet<Poly<GF<32003>>> = rational.iter().map(|p| p.reduce_mod::<32003>().unwrap()).collect();
+
+                let (expected, _) = Ring::buchberger(modular.clone(), Selection::Normal);
+                for selection in [Selection::Normal, Selection::Sugar] {
+                    let (basis, stats) = Ring::f4(modular.clone(), selection);
+                    assert_eq!(basis, expected, "{:?} under {:?}", generators, order);
+                    assert!(stats.matrices > 0 || stats.pairs == stats.product_criterion + stats.chain_criterion);
+                    assert_eq!(stats.pairs, stats.reduced_pairs + stats.product_criterion + stats.chain_criterion);
+                }
+                let (expected, _) = Ring::buchberger(rational.clone(), Selection::Normal);
+                assert_eq!(Ring::f4(rational, Selection::Normal).0, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn ideals() {
+        use std::collections::HashSet;
+        use std::sync::Arc;
+        use coefficient::rational::Rational;
+        use mono::order::*;
+        use poly::poly::Poly;
+        use ring::ideal::Ideal;
+        use ring::ring::Ring;
+
+        let exact = |input: &str| -> Poly<Rational> {
+            return Poly::try_from(&Expr::parse(input).unwrap()).unwrap().to_rational().unwrap();
+        };
+        let ring: Ring<Rational> = Ring::from(HashSet::from([String::from("x"), String::from("y")]));
+        let i: Ideal<Rational> = ring.ideal([exact("x^2 - y"), exact("x*y - 1")]);
+
+        assert!(std::ptr::eq(i.basis(), i.basis()));
+        assert!(i.contains(&exact("x^3 - 1")));
+        assert!(i.contains(&exact("y^3 - 1")));
+        assert!(!i.contains(&exact("x - 1")));
+        assert_eq!(i.normal_form(&exact("x^3 + y")).to_string(), "y + 1");
+        assert!(i.is_zero_dimensional());
+        assert!(!i.is_whole_ring());
+
+        // other generators, and the same ideal under lex
+        let j: Ideal<Rational> = ring.ideal([exact("y - x^2"), exact("x^3 - 1")]);
+        assert!(i == j);
+        assert!(i == j.with_order(Arc::new(Lex)));
+        assert!(i != ring.ideal([exact("x^2 - y")]));
+
+        let cubic: Ideal<Rational> = ring.ideal([exact("x^3 - 1")]);
+        assert!(cubic.is_subset(&i));
+        assert!(!i.is_subset(&cubic));
+        // x^3 = 1 leaves y free in QQ[x, y], but not in QQ[x]
+        assert!(!cubic.is_zero_dimensional() && !ring.ideal([exact("x*y")]).is_zero_dimensional());
+        assert!(Ring::default().ideal([exact("x^3 - 1")]).is_zero_dimensional());
+
+        let whole: Ideal<Rational> = ring.ideal([exact("x + y"), exact("x - y"), exact("y - 1")]);
+        assert!(whole.is_whole_ring());
+        assert!(!whole.is_zero_dimensional());
+        assert!(i.is_subset(&whole));
+    }
 }
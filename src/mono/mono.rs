@@ -7,196 +7,160 @@ use std::fmt::{Display};
 use std::result::*;
 
 use crate::expr::expr::*;
+use crate::expr::print::PrintStyle;
+use crate::coefficient::coefficient::Coefficient;
+use crate::mono::order::{default_order, Order};
 
 #[derive(Clone, Debug)]
 // Mono versus term, mono provides ordering of polynomial ordering of variables
-pub struct Mono {
-	coefficient: Number,
+pub struct Mono<C = Number> {
+	coefficient: C,
 	variables: Variables,
-    ORDER: (Option<String>, bool), // what do you need bool for?
-    e: Option<Expr>
+    ORDER: Option<Order>, // None until the monomial joins an ordered polynomial
 }
 
 // beware do not call default inside itself else stack overflow occurs.
-impl Default for Mono {
+impl<C: Coefficient> Default for Mono<C> {
     fn default() -> Self {
         Mono { 
-            coefficient: Number::Decimal(0.0),
+            coefficient: C::zero(),
             variables: BTreeMap::new(),
-            ORDER: (None, false),
-            e: None
+            ORDER: None,
         }
     }
 }
 
-impl PartialEq for Mono {
+impl<C: Coefficient> PartialEq for Mono<C> {
     fn eq(&self, other: &Self) -> bool {
         self.variables == other.variables
     }
 }
 
-impl Display for Mono {
+impl<C: Coefficient> Display for Mono<C> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.e.clone().unwrap())
+        write!(f, "{}", self.coefficient.term(&self.variables, PrintStyle::Ascii))
     }
 }
 
-impl PartialOrd for Mono {
+impl<C: Coefficient> PartialOrd for Mono<C> {
+    // under the order of `self`, graded lex when it has none
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        assert_eq!(self.ORDER.0, other.ORDER.0);
-        let array_op1: Array1<Number> = Array1::from_vec(self.variables().values().cloned().collect());
-        let array_op2: Array1<Number> = Array1::from_vec(other.variables().values().cloned().collect());
-        let diff: Array1<Number> = array_op1 - array_op2;
-        match self.ORDER.0.clone() {
-            Some(val) => {
-                match val.as_str() {
-                    "LEX" => {
-                        let mut result: Option<Ordering> = None;
-                        for iter in diff.iter() {
-                            // if alpha_1 is equal to beta_1 continue else
-                            if *iter == 0 {
-                                continue;
-                            }
-                            else if *iter > 0 {
-                                result = Some(Ordering::Greater);
-                            }
-                            else {
-                                result = Some(Ordering::Less);
-                            }
-                        }
-                        result
-                    },
-                    "REVGRADLEX" => {
-                        let mut result: Option<Ordering> = None;
-                        for arr_iter in diff.iter().rev() {
-                            // if alpha_1 is equal to beta_1 continue else
-                            if *arr_iter == 0 {
-                                continue;
-                            }
-                            else if *arr_iter > 0 {
-                                result = Some(Ordering::Greater);
-                            }
-                            else {
-                                result = Some(Ordering::Less);
-                            }
-                        }
-                        result
-                    }
-                    _ => panic!("order case is not implemented: use one of REVGRADLEX OR LEX")
-                }
-            },
-            None => panic!("ordering not provide and default ordering has been removed. Kindly set ordering")
-        }
+        let order: Order = self.ORDER.clone().unwrap_or_else(default_order);
+        Some(order.compare(self.variables(), other.variables()))
     }
 }
 
-impl From<(Expr, Option<String>)> for Mono {
-    fn from((e, order): (Expr, Option<String>)) -> Self{
+impl From<(Expr, Option<Order>)> for Mono {
+    fn from((e, order): (Expr, Option<Order>)) -> Self{
         let xterm: Term = e.config_mono();
         // What is the difference between a term and a monomial
         // a term has an order and a monomial does not.
         Mono { 
             coefficient: xterm.coefficient().clone(),
             variables: xterm.variables().clone(),
-            ORDER: (order, true),
-            e: Some(e),
+            ORDER: order,
         }
     }
 }
 
-impl <'a, 'b> Mul<&'b Mono> for &'a Mono {
-    type Output = Result<Mono, bool>;
+impl<C: Coefficient> From<(C, Variables)> for Mono<C> {
+    fn from((coefficient, variables): (C, Variables)) -> Self {
+        Mono {
+            coefficient,
+            variables,
+            ORDER: None,
+        }
+    }
+}
+
+impl <'b, C: Coefficient> Mul<&'b Mono<C>> for &Mono<C> {
+    type Output = Mono<C>;
 
-    fn mul(self, other: &'b Mono) -> Self::Output {
+    fn mul(self, other: &'b Mono<C>) -> Self::Output {
         let (mutable_self, mutable_other) = self.configure(other);
-        let vec1: Array1<Number> = Array1::from(self.variables.values().cloned().collect::<Vec<_>>());
-        let vec2: Array1<Number> = Array1::from(other.variables.values().cloned().collect::<Vec<_>>());
+        let vec1: Array1<Number> = Array1::from(mutable_self.variables.values().cloned().collect::<Vec<_>>());
+        let vec2: Array1<Number> = Array1::from(mutable_other.variables.values().cloned().collect::<Vec<_>>());
 
         let vec: Array1<Number> = vec1 + vec2;
         let k: Vec<String>  = mutable_self.variables.keys().cloned().collect();
         let (vals, _) = vec.into_raw_vec_and_offset();
 
         let coeff = mutable_self.coefficient().clone() * mutable_other.coefficient().clone();
-        let tmp_bmap: BTreeMap<String, Number> = k.iter().zip(vals.iter()).map(|(key, val)| (key.clone(), val.clone())).collect();
-        
-        let e: Expr = Expr::from((coeff.clone(), k, vals));
+        let tmp_bmap: BTreeMap<String, Number> = k.into_iter().zip(vals).collect();
 
-        return Ok(Mono {
+        return Mono {
             coefficient: coeff,
             variables: tmp_bmap,
             ORDER: mutable_self.ORDER,
-            e: Some(e),
-        });
+        };
     }
 }
 
-impl <'a, 'b> Div<&'b Mono> for &'a Mono {
-    type Output = Result<Mono, bool>;
+impl <'a, 'b, C: Coefficient> Div<&'b Mono<C>> for &'a Mono<C> {
+    type Output = Result<Mono<C>, bool>;
 
-    fn div(self, other: &'b Mono) -> Self::Output {
+    fn div(self, other: &'b Mono<C>) -> Self::Output {
         let (mutable_self, mutable_other) = self.configure(other);
-        let vec1: Array1<Number> = Array1::from(self.variables.values().cloned().collect::<Vec<_>>());
-        let vec2: Array1<Number> = Array1::from(other.variables.values().cloned().collect::<Vec<_>>());
+        let vec1: Array1<Number> = Array1::from(mutable_self.variables.values().cloned().collect::<Vec<_>>());
+        let vec2: Array1<Number> = Array1::from(mutable_other.variables.values().cloned().collect::<Vec<_>>());
 
         let vec: Array1<Number> = vec1 - vec2;
         let k: Vec<String>  = mutable_self.variables.keys().cloned().collect();
         let (vals, _) = vec.into_raw_vec_and_offset();
 
-        let coeff = mutable_self.coefficient().clone() / mutable_other.coefficient().clone();
-        let tmp_bmap: BTreeMap<String, Number> = k.iter().zip(vals.iter()).map(|(key, val)| (key.clone(), val.clone())).collect();
-
-        let e: Expr = Expr::from((coeff.clone(), Vec::from(k), vals));
+        let coeff: C = match mutable_self.coefficient().divide(mutable_other.coefficient()) {
+            Some(c) => c,
+            None => return Err(false),
+        };
+        let tmp_bmap: BTreeMap<String, Number> = k.into_iter().zip(vals).collect();
 
         return Ok(Mono {
             coefficient: coeff,
             variables: tmp_bmap,
             ORDER: mutable_self.ORDER,
-            e: Some(e),
         });
     }
 }
 
-impl Div for Mono {
-    type Output = Result<Mono, bool>;
+impl<C: Coefficient> Div for Mono<C> {
+    type Output = Result<Mono<C>, bool>;
 
-    fn div(self, other: Mono) -> Self::Output {
+    fn div(self, other: Mono<C>) -> Self::Output {
         let (mutable_self, mutable_other) = self.configure(&other);
-        let vec1: Array1<Number> = Array1::from(self.variables.values().cloned().collect::<Vec<_>>());
-        let vec2: Array1<Number> = Array1::from(other.variables.values().cloned().collect::<Vec<_>>());
+        let vec1: Array1<Number> = Array1::from(mutable_self.variables.values().cloned().collect::<Vec<_>>());
+        let vec2: Array1<Number> = Array1::from(mutable_other.variables.values().cloned().collect::<Vec<_>>());
 
         let vec: Array1<Number> = vec1 - vec2;
         let k: Vec<String>  = mutable_self.variables.keys().cloned().collect();
         let (vals, _) = vec.into_raw_vec_and_offset();
 
-        let coeff = mutable_self.coefficient().clone() / mutable_other.coefficient().clone();
-        let tmp_bmap: BTreeMap<String, Number> = k.iter().zip(vals.iter()).map(|(key, val)| (key.clone(), val.clone())).collect();
-
-        let e: Expr = Expr::from((coeff.clone(), Vec::from(k), vals));
+        let coeff: C = match mutable_self.coefficient().divide(mutable_other.coefficient()) {
+            Some(c) => c,
+            None => return Err(false),
+        };
+        let tmp_bmap: BTreeMap<String, Number> = k.into_iter().zip(vals).collect();
 
         return Ok(Mono {
             coefficient: coeff,
             variables: tmp_bmap,
             ORDER: mutable_self.ORDER,
-            e: Some(e),
         });
     }
 }
 
-impl <'a, 'b> Add<&'b Mono> for &'a Mono {
-    type Output = Result<Mono, bool>;
+impl <'a, 'b, C: Coefficient> Add<&'b Mono<C>> for &'a Mono<C> {
+    type Output = Result<Mono<C>, bool>;
 
-    fn add(self, other: &'b Mono) -> Self::Output {
+    fn add(self, other: &'b Mono<C>) -> Self::Output {
         // common terms
-        let (new_self, new_other): (Mono, Mono) = self.configure(&other);
+        let (new_self, new_other): (Mono<C>, Mono<C>) = self.configure(&other);
 
         if new_self.common_term(&new_other) {
-            let c: Number = new_self.coefficient().clone() + new_other.coefficient().clone();
-            let e: Expr = Expr::from((c.clone(), new_self.variables.keys().cloned().collect::<Vec<_>>(), new_self.variables.values().cloned().collect::<Vec<_>>()));
+            let c: C = new_self.coefficient().clone() + new_other.coefficient().clone();
             return Ok(Mono {
                 coefficient: c,
                 variables: new_self.variables().clone(),
                 ORDER: new_self.ORDER,
-                e: Some(e)
             });
         }
         else {
@@ -205,21 +169,19 @@ impl <'a, 'b> Add<&'b Mono> for &'a Mono {
     }
 }
 
-impl <'a, 'b> Sub<&'b Mono> for &'a Mono {
-    type Output = Result<Mono, bool>;
+impl <'a, 'b, C: Coefficient> Sub<&'b Mono<C>> for &'a Mono<C> {
+    type Output = Result<Mono<C>, bool>;
 
-    fn sub(self, other: &'b Mono) -> Self::Output {
+    fn sub(self, other: &'b Mono<C>) -> Self::Output {
         // common terms
-        let (new_self, new_other): (Mono, Mono) = self.configure(&other);
+        let (new_self, new_other): (Mono<C>, Mono<C>) = self.configure(&other);
 
         if new_self.common_term(&new_other) {
-            let c: Number = new_self.coefficient().clone() - new_other.coefficient().clone();
-            let e: Expr = Expr::from((c.clone(), new_self.variables.keys().cloned().collect::<Vec<_>>(), new_self.variables.values().cloned().collect::<Vec<_>>()));
+            let c: C = new_self.coefficient().clone() - new_other.coefficient().clone();
             return Ok(Mono {
                 coefficient: c,
                 variables: new_self.variables().clone(),
                 ORDER: new_self.ORDER,
-                e: Some(e)
             });
         }
         else {
@@ -228,19 +190,20 @@ impl <'a, 'b> Sub<&'b Mono> for &'a Mono {
     }
 }
 
-impl Mono {
+impl<C: Coefficient> Mono<C> {
+    // the monomial as an expression tree, built on demand
     pub fn e(&self) -> Expr {
-        self.e.clone().unwrap()
+        Expr::from((self.coefficient.to_number(), self.variables.clone()))
     }
-    pub fn order(&mut self, val: String){
-        self.ORDER.0 = Some(val);
+    pub fn order(&mut self, order: Order){
+        self.ORDER = Some(order);
     }
 
-    pub fn coefficient (&self) -> &Number {
+    pub fn coefficient (&self) -> &C {
         return &self.coefficient;
     }
 
-    pub fn coefficient_mut (&self) -> Number {
+    pub fn coefficient_mut (&self) -> C {
         let result = self.coefficient().clone();
         result
     }
@@ -254,10 +217,10 @@ impl Mono {
         result
     }
 
-    pub fn configure(&self, other: &Mono) -> (Mono, Mono) {
+    pub fn configure(&self, other: &Mono<C>) -> (Mono<C>, Mono<C>) {
         let mut keys: BTreeSet<String> = BTreeSet::new();
-        let mut mutable_self = Mono::default();
-        let mut mutable_other: Mono = Mono::default();
+        let mut mutable_self: Mono<C> = Mono::default();
+        let mut mutable_other: Mono<C> = Mono::default();
 
         mutable_self.coefficient = self.coefficient.clone();
         mutable_other.coefficient = other.coefficient.clone();
@@ -298,36 +261,33 @@ impl Mono {
 
     }
 
-    pub fn cofactor(&self, other: &Mono) -> Result<Mono, bool> {
-        if self.variables.keys().all(|key| other.variables.contains_key(key)) {
-            self.configure(other);
-        }
-        let vars = self.variables.keys();
-        let deg1: Array1<Number> = Array1::from(self.variables.values().cloned().collect::<Vec<_>>());
-        let deg2: Array1<Number> = Array1::from(other.variables.values().cloned().collect::<Vec<_>>());
+    // other / self when self divides other, Err(false) otherwise
+    pub fn cofactor(&self, other: &Mono<C>) -> Result<Mono<C>, bool> {
+        let (mutable_self, mutable_other) = self.configure(other);
+        let vars = mutable_self.variables.keys();
+        let deg1: Array1<Number> = Array1::from(mutable_self.variables.values().cloned().collect::<Vec<_>>());
+        let deg2: Array1<Number> = Array1::from(mutable_other.variables.values().cloned().collect::<Vec<_>>());
         let diff: Array1<Number> = deg2 - deg1;
-        let d_coef: Number = other.coefficient().clone() / self.coefficient().clone();
 
         for val in diff.iter() {
             if *val < 0 {
                 return Err(false);
             }
         }
+        let d_coef: C = match other.coefficient().divide(self.coefficient()) {
+            Some(c) => c,
+            None => return Err(false),
+        };
         let k: Vec<String>  = vars.cloned().collect();
         let (vals, _) = diff.into_raw_vec_and_offset();
         let tmp_bmap: BTreeMap<String, Number> = k.iter().zip(vals.iter()).map(|(key, val)| (key.clone(), val.clone())).collect();
-        
-        let _e: Expr = Expr::from((d_coef.clone(), k, vals));
 
-        Ok(Mono {
-            coefficient: d_coef,
-            variables: tmp_bmap,
-            ORDER: self.ORDER.clone(),
-            e: None
-        })
+        let mut result: Mono<C> = Mono::from((d_coef, tmp_bmap));
+        result.ORDER = self.ORDER.clone();
+        Ok(result)
     }
 
-    pub fn common_term(&self, other: &Mono) -> bool {
+    pub fn common_term(&self, other: &Mono<C>) -> bool {
         match self.variables == other.variables {
             true => {
                         let a: Array1<Number> = Array1::from(self.variables().values().cloned().collect::<Vec<_>>());
@@ -341,15 +301,13 @@ impl Mono {
         }
     }
 
-    pub fn update_expr(&mut self) {
-        let tmp_mono = self.clone();
-        self.e = Some(Expr::from((tmp_mono.coefficient, tmp_mono.variables))); //only mut self becuase muting e is important
-    }
-
     pub fn remove_zero(&mut self) -> bool {
         let mut rm_entry = None;
         let mut tmp_variables = self.variables.clone();
-        for (key, _val) in self.variables.iter_mut() {
+        for (key, val) in self.variables.iter_mut() {
+            if *val != Number::Decimal(0.0) {
+                continue;
+            }
             match tmp_variables.remove(&key.clone()) {
                 Some(val) => { 
                     rm_entry = Some(val);
@@ -368,7 +326,7 @@ impl Mono {
     // also updates by zeroing monomials
     pub fn zero(&mut self) -> bool {
         self.remove_zero();
-        if self.coefficient != Number::Decimal(0.0) {
+        if !self.coefficient.is_zero() {
             return false;
         } else {
             for (_, val) in self.variables.iter() {
@@ -380,9 +338,9 @@ impl Mono {
         true
     }
 
-    pub fn lcm(a: &Mono, b: &Mono) -> Mono {
+    pub fn lcm(a: &Mono<C>, b: &Mono<C>) -> Mono<C> {
         let mut keys: BTreeSet<String> = BTreeSet::new();
-        let mut lcm_mono: Mono = Mono::default();
+        let mut lcm_mono: Mono<C> = Mono::default();
         for self_ch in a.variables.keys() {
             keys.insert(self_ch.clone());
         }
@@ -400,9 +358,8 @@ impl Mono {
             };
             lcm_mono.variables.insert(k.clone(), deg_a.max(deg_b));
         }
-        lcm_mono.coefficient = Number::Decimal(1.0);
+        lcm_mono.coefficient = C::one();
         lcm_mono.ORDER = a.ORDER.clone();
-        lcm_mono.update_expr();
         lcm_mono
     }
 }
\ No newline at end of file
@@ -0,0 +1,163 @@
+use std::cmp::Ordering;
+use std::collections::*;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use crate::expr::expr::*;
+
+// Monomial orders.
+//
+// An order compares two monomials given by their exponents. Variables are
+// ranked by name, so x > y > z, and a variable missing from a monomial has
+// exponent zero. `Lex`, `GrLex` and `GRevLex` are the usual orders, `Weight`
+// compares a weighted degree first and `Block` compares the variables of
+// each block in turn, which gives elimination orders. Any other type
+// implementing `MonomialOrder` can be set on a `Poly` or a `Ring`; it should
+// be a total order compatible with multiplication for division and Gröbner
+// bases to terminate.
+
+pub trait MonomialOrder: Debug + Send + Sync {
+    fn compare(&self, a: &Variables, b: &Variables) -> Ordering;
+}
+
+pub type Order = Arc<dyn MonomialOrder>;
+
+// exponents of `a` and `b` over the variables of both, greatest variable first
+pub fn exponent_vectors(a: &Variables, b: &Variables) -> (Vec<f64>, Vec<f64>) {
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    let degree = |v: &Variables, k: &String| -> f64 {
+        match v.get(k) {
+            Some(n) => f64::from(n.clone()),
+            None => 0.0,
+        }
+    };
+    let va: Vec<f64> = keys.iter().map(|k| degree(a, k)).collect();
+    let vb: Vec<f64> = keys.iter().map(|k| degree(b, k)).collect();
+    return (va, vb);
+}
+
+fn lex(va: &[f64], vb: &[f64]) -> Ordering {
+    for (x, y) in va.iter().zip(vb.iter()) {
+        if x != y {
+            return x.total_cmp(y);
+        }
+    }
+    return Ordering::Equal;
+}
+
+fn total(v: &[f64]) -> f64 {
+    return v.iter().sum();
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Lex;
+
+impl MonomialOrder for Lex {
+    fn compare(&self, a: &Variables, b: &Variables) -> Ordering {
+        let (va, vb) = exponent_vectors(a, b);
+        return lex(&va, &vb);
+    }
+}
+
+// total degree, ties broken by lex
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GrLex;
+
+impl MonomialOrder for GrLex {
+    fn compare(&self, a: &Variables, b: &Variables) -> Ordering {
+        let (va, vb) = exponent_vectors(a, b);
+        return total(&va).total_cmp(&total(&vb)).then(lex(&va, &vb));
+    }
+}
+
+// total degree, ties go to the smaller exponent in the last variable that differs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GRevLex;
+
+impl MonomialOrder for GRevLex {
+    fn compare(&self, a: &Variables, b: &Variables) -> Ordering {
+        let (va, vb) = exponent_vectors(a, b);
+        let ra: Vec<f64> = va.iter().rev().cloned().collect();
+        let rb: Vec<f64> = vb.iter().rev().cloned().collect();
+        return total(&va).total_cmp(&total(&vb)).then(lex(&rb, &ra));
+    }
+}
+
+// weighted degree, variables without a weight count zero, ties broken by `tie`
+#[derive(Clone, Debug)]
+pub struct Weight {
+    pub weights: BTreeMap<String, f64>,
+    pub tie: Order,
+}
+
+impl Weight {
+    // ties broken by grevlex
+    pub fn new(weights: &[(&str, f64)]) -> Weight {
+        return Weight {
+            weights: weights.iter().map(|(s, w)| (s.to_string(), *w)).collect(),
+            tie: Arc::new(GRevLex),
+        };
+    }
+
+    fn degree(&self, v: &Variables) -> f64 {
+        return v.iter()
+            .map(|(s, n)| self.weights.get(s).copied().unwrap_or(0.0) * f64::from(n.clone()))
+            .sum();
+    }
+}
+
+impl MonomialOrder for Weight {
+    fn compare(&self, a: &Variables, b: &Variables) -> Ordering {
+        return self.degree(a).total_cmp(&self.degree(b)).then_with(|| self.tie.compare(a, b));
+    }
+}
+
+// the variables of each block compared with that block's order, first block first;
+// variables in no block come last under `rest`
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub blocks: Vec<(BTreeSet<String>, Order)>,
+    pub rest: Order,
+}
+
+impl Block {
+    pub fn new(blocks: Vec<(&[&str], Order)>, rest: Order) -> Block {
+        return Block {
+            blocks: blocks.into_iter()
+                .map(|(vars, order)| (vars.iter().map(|s| s.to_string()).collect(), order))
+                .collect(),
+            rest,
+        };
+    }
+
+    // any monomial in `eliminate` is greater than all monomials without them,
+    // grevlex within both blocks
+    pub fn elimination(eliminate: &[&str]) -> Block {
+        return Block::new(vec![(eliminate, Arc::new(GRevLex))], Arc::new(GRevLex));
+    }
+
+    fn restrict(v: &Variables, keep: impl Fn(&String) -> bool) -> Variables {
+        return v.iter().filter(|(s, _)| keep(s)).map(|(s, n)| (s.clone(), n.clone())).collect();
+    }
+}
+
+impl MonomialOrder for Block {
+    fn compare(&self, a: &Variables, b: &Variables) -> Ordering {
+        for (vars, order) in self.blocks.iter() {
+            let ordering: Ordering = order.compare(
+                &Block::restrict(a, |s| vars.contains(s)),
+                &Block::restrict(b, |s| vars.contains(s)),
+            );
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+        let outside = |s: &String| -> bool { !self.blocks.iter().any(|(vars, _)| vars.contains(s)) };
+        return self.rest.compare(&Block::restrict(a, outside), &Block::restrict(b, outside));
+    }
+}
+
+// the order of a Poly or Mono that was not given one
+pub fn default_order() -> Order {
+    return Arc::new(GrLex);
+}
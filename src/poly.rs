@@ -1 +1,9 @@
-pub mod poly;
\ No newline at end of file
+// lints the original module trips, including the `ORDER` field
+#[allow(non_snake_case)]
+#[allow(clippy::module_inception, clippy::redundant_field_names, clippy::unnecessary_cast)]
+#[allow(clippy::needless_bool, clippy::single_match)]
+pub mod poly;
+pub mod subs;
+pub mod expand;
+pub mod calculus;
+pub mod horner;
\ No newline at end of file
@@ -0,0 +1,67 @@
+use num_notation::*;
+
+use crate::mono::mono::*;
+use crate::expr::expr::*;
+use crate::poly::poly::Poly;
+use crate::poly::expand::natural;
+use crate::coefficient::coefficient::Coefficient;
+
+// Exact derivative and antiderivative of a polynomial, monomial by monomial.
+// The factors n and n + 1 are built in the coefficient domain, so both stay
+// exact over `Rational`; exponents of the variable must be natural numbers.
+
+fn degree<C: Coefficient>(mono: &Mono<C>, var: &str) -> Option<u32> {
+    match mono.variables().get(var) {
+        Some(n) => natural(n),
+        None => Some(0),
+    }
+}
+
+// n as a coefficient, 1 + 1 + ... + 1 by doubling
+fn factor<C: Coefficient>(n: u32) -> C {
+    let mut result: C = C::zero();
+    let mut power: C = C::one();
+    let mut n: u32 = n;
+    while n > 0 {
+        if n & 1 == 1 {
+            result = result + power.clone();
+        }
+        power = power.clone() + power;
+        n >>= 1;
+    }
+    return result;
+}
+
+impl<C: Coefficient> Poly<C> {
+    // None when a term holds var to a power that is not a natural number
+    pub fn derivative(&self, var: &str) -> Option<Poly<C>> {
+        let mut monos: Vec<Mono<C>> = Vec::new();
+        for mono in self.monomials.values() {
+            let n: u32 = degree(mono, var)?;
+            if n == 0 {
+                continue;
+            }
+            let mut variables: Variables = mono.variables().clone();
+            variables.insert(var.to_string(), Number::Decimal(f64::from(n - 1)));
+            monos.push(Mono::from((mono.coefficient().clone() * factor(n), variables)));
+        }
+        let mut result: Poly<C> = self.zero_like();
+        result.extend(monos);
+        return Some(result);
+    }
+
+    // antiderivative without integration constant, None when a term holds var
+    // to a power that is not a natural number or n + 1 cannot be divided by
+    pub fn integrate(&self, var: &str) -> Option<Poly<C>> {
+        let mut monos: Vec<Mono<C>> = Vec::new();
+        for mono in self.monomials.values() {
+            let n: u32 = degree(mono, var)?.checked_add(1)?;
+            let mut variables: Variables = mono.variables().clone();
+            variables.insert(var.to_string(), Number::Decimal(f64::from(n)));
+            monos.push(Mono::from((mono.coefficient().divide(&factor(n))?, variables)));
+        }
+        let mut result: Poly<C> = self.zero_like();
+        result.extend(monos);
+        return Some(result);
+    }
+}
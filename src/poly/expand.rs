@@ -0,0 +1,124 @@
+use num_notation::*;
+use std::fmt::Display;
+
+use crate::mono::mono::*;
+use crate::expr::expr::*;
+use crate::poly::poly::Poly;
+
+// Expansion of an `Expr` into a sum of `Mono`s.
+//
+// Sums, differences, negation and products are multiplied out, SQUARE and POW
+// need a natural constant exponent, DIV needs a divisor that expands to a
+// nonzero constant. Anything else is not a polynomial and is reported with the
+// offending node, as are powers above `MAX_EXPONENT`.
+
+// largest exponent a power of a polynomial is multiplied out to
+pub(crate) const MAX_EXPONENT: u32 = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpandError {
+    pub node: String, // the subexpression that is not polynomial, printed
+    pub message: String,
+}
+
+impl ExpandError {
+    fn new(e: &Expr, message: &str) -> Self {
+        ExpandError { node: e.to_string(), message: message.to_string() }
+    }
+}
+
+impl Display for ExpandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}`: {}", self.node, self.message)
+    }
+}
+
+impl std::error::Error for ExpandError {}
+
+fn constant_poly(c: Number) -> Poly {
+    let mut result: Poly = Poly::default();
+    result.insert_mono(Mono::from((c, Variables::new())));
+    return result;
+}
+
+pub(crate) fn natural(n: &Number) -> Option<u32> {
+    let val: f64 = f64::from(n.clone());
+    if val >= 0.0 && val.fract() == 0.0 && val <= f64::from(u32::MAX) {
+        return Some(val as u32);
+    }
+    return None;
+}
+
+// value of a polynomial without variables, zero for the empty polynomial
+fn constant_value(p: &Poly) -> Option<Number> {
+    match p.monomials.len() {
+        0 => Some(Number::Decimal(0.0)),
+        1 => {
+            let mono: &Mono = p.monomials.values().next().unwrap();
+            if mono.variables().values().all(|n| *n == 0.0) {
+                return Some(mono.coefficient().clone());
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+// square and multiply, from the most significant bit of `n`
+pub(crate) fn power(base: &Poly, n: u32) -> Poly {
+    let mut result: Poly = constant_poly(Number::Decimal(1.0));
+    for bit in (0..u32::BITS - n.leading_zeros()).rev() {
+        result = result.clone() * result;
+        if n >> bit & 1 == 1 {
+            result = result * base.clone();
+        }
+    }
+    return result;
+}
+
+fn expand(e: &Expr) -> Result<Poly, ExpandError> {
+    match e.op() {
+        EnumExpr::CONSTANT => Ok(constant_poly(e.x.coefficient.clone())),
+        EnumExpr::PARAM => {
+            if e.x.variables.values().any(|n| natural(n).is_none()) {
+                return Err(ExpandError::new(e, "exponents must be natural numbers"));
+            }
+            let mut result: Poly = Poly::default();
+            result.insert_mono(Mono::from((e.x.coefficient.clone(), e.x.variables.clone())));
+            Ok(result)
+        }
+        EnumExpr::PLUS => Ok(expand(e.a.as_ref().unwrap())? + expand(e.b.as_ref().unwrap())?),
+        EnumExpr::MINUS => Ok(expand(e.a.as_ref().unwrap())? - expand(e.b.as_ref().unwrap())?),
+        EnumExpr::TIMES => Ok(expand(e.a.as_ref().unwrap())? * expand(e.b.as_ref().unwrap())?),
+        EnumExpr::NEGATE => Ok(constant_poly(Number::Decimal(-1.0)) * expand(e.a.as_ref().unwrap())?),
+        EnumExpr::SQUARE => Ok(power(&expand(e.a.as_ref().unwrap())?, 2)),
+        EnumExpr::POW => {
+            let exponent: Option<Number> = expand(e.b.as_ref().unwrap()).ok().and_then(|p| constant_value(&p));
+            match exponent.as_ref().and_then(natural) {
+                Some(n) if n > MAX_EXPONENT => Err(ExpandError::new(e, &format!("exponent exceeds {}", MAX_EXPONENT))),
+                Some(n) => Ok(power(&expand(e.a.as_ref().unwrap())?, n)),
+                None => Err(ExpandError::new(e, "exponent is not a natural number")),
+            }
+        }
+        EnumExpr::DIV => {
+            let divisor: Option<Number> = expand(e.b.as_ref().unwrap()).ok().and_then(|p| constant_value(&p));
+            match divisor {
+                Some(c) if c != 0.0 => {
+                    let inverse: Number = Number::Decimal(1.0 / f64::from(c));
+                    Ok(constant_poly(inverse) * expand(e.a.as_ref().unwrap())?)
+                }
+                Some(_) => Err(ExpandError::new(e, "division by zero")),
+                None => Err(ExpandError::new(e, "divisor is not a constant")),
+            }
+        }
+        _ => Err(ExpandError::new(e, &format!("{} is not a polynomial operation", e.op()))),
+    }
+}
+
+impl TryFrom<&Expr> for Poly {
+    type Error = ExpandError;
+
+    fn try_from(e: &Expr) -> Result<Self, Self::Error> {
+        return expand(e);
+    }
+}
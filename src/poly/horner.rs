@@ -0,0 +1,94 @@
+use std::collections::*;
+use ndarray::*;
+
+use crate::expr::batch::{BatchError, Columns};
+use crate::poly::poly::Poly;
+
+// Batch evaluation of a polynomial by a multivariate Horner scheme.
+//
+// The monomials are grouped by their degree in the first variable, each group
+// is evaluated recursively in the remaining variables, and the groups are
+// combined as ((q_n x + q_(n-1)) x + ...) x + q_0. A variable with a negative
+// or fractional exponent cannot be nested this way, its monomials are summed
+// directly instead. A variable of the polynomial without a column is a
+// `BatchError`.
+
+// coefficient and exponents in the order of the columns
+type Term = (f64, Vec<f64>);
+
+fn natural(exp: f64) -> bool {
+    return exp >= 0.0 && exp.fract() == 0.0;
+}
+
+fn direct(terms: &[&Term], k: usize, columns: &Columns) -> Array1<f64> {
+    let mut result: Array1<f64> = Array1::zeros(columns.len);
+    for (c, exps) in terms.iter() {
+        let mut term: Array1<f64> = Array1::from_elem(columns.len, *c);
+        for (j, exp) in exps.iter().enumerate().skip(k) {
+            if *exp != 0.0 {
+                term.zip_mut_with(&columns.columns[j], |t, &x| *t *= x.powf(*exp));
+            }
+        }
+        result += &term;
+    }
+    return result;
+}
+
+fn horner(terms: &[&Term], k: usize, columns: &Columns) -> Array1<f64> {
+    if k == columns.columns.len() {
+        return Array1::from_elem(columns.len, terms.iter().map(|(c, _)| c).sum());
+    }
+    if !terms.iter().all(|(_, exps)| natural(exps[k])) {
+        return direct(terms, k, columns);
+    }
+    let mut groups: BTreeMap<u32, Vec<&Term>> = BTreeMap::new();
+    for term in terms.iter() {
+        groups.entry(term.1[k] as u32).or_default().push(term);
+    }
+    let n: u32 = match groups.keys().next_back() {
+        Some(n) => *n,
+        None => return Array1::zeros(columns.len),
+    };
+    let mut result: Array1<f64> = horner(&groups[&n], k + 1, columns);
+    for d in (0..n).rev() {
+        result *= &columns.columns[k];
+        if let Some(group) = groups.get(&d) {
+            result += &horner(group, k + 1, columns);
+        }
+    }
+    return result;
+}
+
+impl Poly {
+    fn horner_terms(&self, variables: &[&str]) -> Result<Vec<Term>, BatchError> {
+        let mut terms: Vec<Term> = Vec::new();
+        for mono in self.monomials.values() {
+            let mut exps: Vec<f64> = vec![0.0; variables.len()];
+            for (name, exp) in mono.variables().iter() {
+                let exp: f64 = f64::from(exp.clone());
+                match variables.iter().position(|v| v == name) {
+                    Some(i) => exps[i] = exp,
+                    None if exp == 0.0 => {},
+                    None => return Err(BatchError::new(self, format!("no column for variable {}", name))),
+                }
+            }
+            terms.push((f64::from(mono.coefficient().clone()), exps));
+        }
+        return Ok(terms);
+    }
+
+    // evaluates at every sample, `columns[i]` holds the values of `variables[i]`
+    pub fn eval_array(&self, variables: &[&str], columns: &[ArrayView1<f64>]) -> Result<Array1<f64>, BatchError> {
+        let terms: Vec<Term> = self.horner_terms(variables)?;
+        let columns: Columns = Columns::new(variables, columns, 1).map_err(|message| BatchError::new(self, message))?;
+        return Ok(horner(&terms.iter().collect::<Vec<&Term>>(), 0, &columns));
+    }
+
+    // evaluates at every row of `points`, column i holds the values of `variables[i]`
+    pub fn eval_points(&self, variables: &[&str], points: ArrayView2<f64>) -> Result<Array1<f64>, BatchError> {
+        let terms: Vec<Term> = self.horner_terms(variables)?;
+        let columns: Vec<ArrayView1<f64>> = points.columns().into_iter().collect();
+        let columns: Columns = Columns::new(variables, &columns, points.nrows()).map_err(|message| BatchError::new(self, message))?;
+        return Ok(horner(&terms.iter().collect::<Vec<&Term>>(), 0, &columns));
+    }
+}
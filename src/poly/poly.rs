@@ -6,18 +6,23 @@ use std::ops::*; // used for index trait
 use std::fmt::{Display, Formatter};
 
 use crate::mono::mono::*;
+use crate::mono::order::*;
 use crate::expr::expr::*;
-
-pub struct Poly {
-    ORDER: (String, bool),
-    monomials: BTreeMap<String, Mono>, // if the Mono are transient during calculations how efficient is the hash of their exp as opposed to using a standard hashing function
-    e: Expr,
+use crate::expr::print::PrintStyle;
+use crate::coefficient::coefficient::Coefficient;
+use crate::coefficient::rational::Rational;
+
+#[derive(Debug)]
+pub struct Poly<C = Number> {
+    pub(crate) ORDER: Order,
+    pub(crate) monomials: BTreeMap<String, Mono<C>>, // if the Mono are transient during calculations how efficient is the hash of their exp as opposed to using a standard hashing function
+    pub(crate) e: Expr,
 }
 
-impl Default for Poly {
+impl<C: Coefficient> Default for Poly<C> {
     fn default() -> Self {
         Self {
-            ORDER: ("LEV".to_string(), true),
+            ORDER: default_order(),
             monomials: BTreeMap::new(),
             e: Expr::default(),
         }
@@ -27,7 +32,7 @@ impl Default for Poly {
 impl From<Expr> for Poly{
     fn from(e: Expr) -> Self {
         Self {
-            ORDER: ("LEV".to_string(), true),
+            ORDER: default_order(),
             monomials: BTreeMap::new(),
             e: e,
         }
@@ -36,47 +41,29 @@ impl From<Expr> for Poly{
 
 impl From<HashMap<String, Expr>> for Poly {
     fn from(map: HashMap<String, Expr>) -> Self {
-        let mut mono_map: HashMap<String, Mono> = HashMap::new();
-        for (_, mono_expr) in map.iter() {
-            let mono_expr_clone = mono_expr.clone();
-            let mono_string: String = mono_expr_clone.to_string();
-            let tmp_mono: Mono = Mono::from((mono_expr_clone, Some(mono_string.clone())));
-            mono_map.insert(mono_string, tmp_mono);
-        }
-        let e: Expr = Expr::from(mono_map.clone());
-        Self {
-            ORDER: ("GLEV".to_string(), true),
-            monomials: mono_map.into_iter().collect(),
-            e: e,
-        }
+        let mut result: Poly = Poly::default();
+        result.extend(map.into_values().map(|mono_expr| Mono::from((mono_expr, None))));
+        return result;
     }
 }
 
-impl From<Mono> for Poly{  
-    fn from(mono: Mono) -> Self {
-        let e: Expr = Expr::from(mono.clone());
-        Self {
-            ORDER: ("GLEV".to_string(), true),
-            monomials: BTreeMap::from([
-                (mono.clone().to_string(), mono)
-            ]),
-            e: e,
-        }
+impl<C: Coefficient> From<Mono<C>> for Poly<C> {
+    fn from(mono: Mono<C>) -> Self {
+        let mut result: Poly<C> = Poly::default();
+        result.extend([mono]);
+        return result;
     }
 }
 
-impl From<BTreeMap<String, Mono>> for Poly{  
-    fn from(map: BTreeMap<String, Mono>) -> Self {
-        let e: Expr = Expr::from(map.clone());
-        Self {
-            ORDER: ("GLEV".to_string(), true),
-            monomials: map.into_iter().collect(),
-            e: e,
-        }
+impl<C: Coefficient> From<BTreeMap<String, Mono<C>>> for Poly<C> {
+    fn from(map: BTreeMap<String, Mono<C>>) -> Self {
+        let mut result: Poly<C> = Poly::default();
+        result.extend(map.into_values());
+        return result;
     }
 }
 
-impl Poly {
+impl<C: Coefficient> Poly<C> {
     // correct configure function // corrcted to mean an ordered polynomial with monomials as ring of all variables in the polynomial.
     pub fn configure(&mut self) {
         let mut keys: BTreeSet<String> = BTreeSet::new();
@@ -97,6 +84,10 @@ impl Poly {
         }
     }
 
+    pub fn e(&self) -> Expr {
+        self.e.clone()
+    }
+
     pub fn kind(&self) -> EnumExpr {
         self.e.op()
     }
@@ -110,12 +101,16 @@ impl Poly {
         while !queue.is_empty() {
             let exp = queue.pop_front().unwrap();
             if !(exp.op() == EnumExpr::CONSTANT || exp.op() == EnumExpr::PARAM || exp.op() == EnumExpr::PARAM_PTR) {
-                if exp.children() == 2 {
+                if exp.children() >= 2 {
                     queue.push_back(exp.a().unwrap().as_ref().clone()); // unwrap panics if no element was found
                     result.push_back(exp.a().unwrap().as_ref().clone());
                     
                     queue.push_back(exp.b().unwrap().as_ref().clone());
                     result.push_back(exp.b().unwrap().as_ref().clone());
+                    if exp.children() == 3 {
+                        queue.push_back(exp.c().unwrap().as_ref().clone());
+                        result.push_back(exp.c().unwrap().as_ref().clone());
+                    }
                 } else if exp.children() == 1 {
                     queue.push_back(exp.a().unwrap().as_ref().clone());
                     result.push_back(exp.a().unwrap().as_ref().clone());
@@ -134,7 +129,24 @@ impl Poly {
         return ops[i].clone();
     }
 
-    pub fn monomials(&self) -> BTreeMap<String, Mono> {
+    pub fn order(&self) -> Order {
+        return self.ORDER.clone();
+    }
+
+    // orders the monomials of `self` by `order` from now on
+    pub fn set_order(&mut self, order: Order) {
+        for mono in self.monomials.values_mut() {
+            mono.order(order.clone());
+        }
+        self.ORDER = order;
+    }
+
+    pub fn with_order(mut self, order: Order) -> Poly<C> {
+        self.set_order(order);
+        return self;
+    }
+
+    pub fn monomials(&self) -> BTreeMap<String, Mono<C>> {
         return self.monomials.clone();
     }
 
@@ -142,74 +154,44 @@ impl Poly {
         return self.e.monomial_gme();
     }
 
-    pub fn leading_term(&mut self) -> &Mono { //only mut self because order method is used here
-        if self.ORDER.1 == false {
-            return self.monomials.values().next().unwrap();
-        }
-        else {
-            self.order(self.ORDER.0.clone().as_str());
-            return self.monomials.values().next().unwrap();
-        }
+    // greatest monomial under the active order, see `sorted_monomials`
+    pub fn leading_term(&mut self) -> &Mono<C> { //only mut self because order method is used here
+        let key: String = self.monomials.iter()
+            .max_by(|a, b| self.ORDER.compare(a.1.variables(), b.1.variables()))
+            .map(|(k, _)| k.clone())
+            .expect("the zero polynomial has no leading term");
+        return &self.monomials[&key];
     }
 
-    pub fn leading_coefficient(&mut self) -> Number { // only mut self because leading term is mut self
-        let term: &Mono = self.leading_term();
+    pub fn leading_coefficient(&mut self) -> C { // only mut self because leading term is mut self
+        let term: &Mono<C> = self.leading_term();
         return term.coefficient().clone();
     }
 
-    fn order(&mut self, val: &str) {
-
-        fn divide(items: Vec<Mono>) -> Vec<Mono>  {
-            match items.len() {
-                0 | 1 => return items ,
-                _ => {
-                    let mid = items.len() / 2;
-                    let left = items[..mid].to_vec();
-                    let right = items[mid..].to_vec();
-                    return merge_sort(left, right);
-                }
-            }
-            
-        }
-
-        fn merge_sort(a: Vec<Mono>, b: Vec<Mono>) -> Vec<Mono> {
-            let mut i: usize = 0;
-            let mut j: usize = 0;
-
-            let mut result: Vec<Mono> = Vec::new();
-            while i < a.len() && j < b.len() {
-                if a[i] <= b[j] {
-                    result.push(a[i].clone());
-                    i += 1;
-                } else {
-                    result.push(b[j].clone());
-                    j += 1;
-                }
-            }
-
-            if i < a.len() {
-                result.extend_from_slice(&a[i..]);
-            }
-            if j < b.len() {
-                result.extend_from_slice(&b[j..]);
-            }
-            return result;
-        }
-
-        self.ORDER.0 = val.to_string();
-        for mono in self.monomials.values_mut() {
-            mono.order(val.to_string());
+    // `self` scaled to leading coefficient one, unchanged when that coefficient is not a unit
+    pub fn monic(&self) -> Poly<C> {
+        if self.monomials.is_empty() {
+            return self.clone();
         }
-
-        let vec_mono = self.monomials.values().cloned().collect();
-        let sorted_vec_mono = divide(vec_mono);
-
-        for (mono, sorted_mono) in self.monomials.values_mut().zip(sorted_vec_mono.into_iter()) {
-            *mono = sorted_mono;
+        let mut p: Poly<C> = self.clone();
+        match p.leading_coefficient().inverse() {
+            Some(inverse) => return p * Mono::from((inverse, Variables::new())),
+            None => return p,
         }
+    }
 
-        self.ORDER.1 = true;
-        
+    // the same monomials over another domain, None as soon as `f` fails on a coefficient
+    pub fn map_coefficients<D: Coefficient>(&self, f: impl Fn(&C) -> Option<D>) -> Option<Poly<D>> {
+        let mut result: Poly<D> = Poly {
+            ORDER: self.ORDER.clone(),
+            monomials: BTreeMap::new(),
+            e: Expr::from(0.0),
+        };
+        let monos: Option<Vec<Mono<D>>> = self.monomials.values()
+            .map(|mono| Some(Mono::from((f(mono.coefficient())?, mono.variables().clone()))))
+            .collect();
+        result.extend(monos?);
+        return Some(result);
     }
 
     pub fn remove_zero(&mut self) -> bool {
@@ -243,14 +225,106 @@ impl Poly {
         }
     }
 
-    pub fn update_expr(&mut self) {
-        for (_, mono) in self.monomials.iter_mut() { //only mut self becuase muting e is important
-            mono.update_expr();
+    // monomials from leading to trailing under the order of `self`
+    pub fn sorted_monomials(&self) -> Vec<Mono<C>> {
+        let mut monos: Vec<Mono<C>> = self.monomials.values().cloned().collect();
+        monos.sort_by(|a, b| self.ORDER.compare(b.variables(), a.variables()));
+        return monos;
+    }
+
+    pub fn pretty(&self, style: PrintStyle) -> String {
+        if self.monomials.is_empty() {
+            return self.e.pretty(style);
+        }
+        let (plus, minus) = match style {
+            PrintStyle::Compact => ("+", "-"),
+            _ => (" + ", " - "),
+        };
+        let mut result: String = String::new();
+        for (i, mono) in self.sorted_monomials().iter().enumerate() {
+            let mut coefficient: C = mono.coefficient().clone();
+            if i > 0 {
+                if coefficient.is_negative() {
+                    coefficient = -coefficient;
+                    result.push_str(minus);
+                }
+                else {
+                    result.push_str(plus);
+                }
+            }
+            result.push_str(&coefficient.term(mono.variables(), style));
+        }
+        return result;
+    }
+
+    // empty polynomial keeping the order of `self`
+    pub(crate) fn zero_like(&self) -> Poly<C> {
+        Poly {
+            ORDER: self.ORDER.clone(),
+            monomials: BTreeMap::new(),
+            e: Expr::from(0.0),
+        }
+    }
+
+    // adds a monomial under its `mono_key`, merging it with a like monomial and
+    // dropping it when the coefficients cancel; the Expr view is left to the caller
+    fn merge_mono(&mut self, mono: Mono<C>) {
+        let key: String = mono_key(mono.variables());
+        let coefficient: C = match self.monomials.remove(&key) {
+            Some(old_mono) => mono.coefficient().clone() + old_mono.coefficient().clone(),
+            None => mono.coefficient().clone(),
+        };
+        if coefficient.is_zero() {
+            return;
         }
+        let variables: Variables = mono.variables().iter()
+            .filter(|(_, n)| **n != 0.0)
+            .map(|(s, n)| (s.clone(), n.clone()))
+            .collect();
+        self.monomials.insert(key, Mono::from((coefficient, variables)));
+    }
+
+    pub(crate) fn insert_mono(&mut self, mono: Mono<C>) {
+        self.extend([mono]);
+    }
+}
+
+// merges the monomials one by one and rebuilds the Expr view once
+impl<C: Coefficient> Extend<Mono<C>> for Poly<C> {
+    fn extend<I: IntoIterator<Item = Mono<C>>>(&mut self, monos: I) {
+        for mono in monos {
+            self.merge_mono(mono);
+        }
+        self.e = Expr::from(self.monomials.clone());
+    }
+}
+
+impl Poly {
+    // the same polynomial with exact coefficients, see `Rational::from_f64`
+    pub fn to_rational(&self) -> Result<Poly<Rational>, String> {
+        let mut result: Poly<Rational> = Poly {
+            ORDER: self.ORDER.clone(),
+            monomials: BTreeMap::new(),
+            e: Expr::from(0.0),
+        };
+        let monos: Result<Vec<Mono<Rational>>, String> = self.monomials.values()
+            .map(|mono| Ok(Mono::from((Rational::try_from(mono.coefficient())?, mono.variables().clone()))))
+            .collect();
+        result.extend(monos?);
+        return Ok(result);
     }
 }
 
-impl Hash for Poly {
+// the monomial printed without coefficient and zero exponents, e.g. `x^2*y`
+pub(crate) fn mono_key(variables: &Variables) -> String {
+    let unit: Variables = variables.iter()
+        .filter(|(_, n)| **n != 0.0)
+        .map(|(s, n)| (s.clone(), n.clone()))
+        .collect();
+    return Term::from((Number::Decimal(1.0), unit)).to_string();
+}
+
+impl<C: Coefficient> Hash for Poly<C> {
     // Note: No generic parameters for Self here
     fn hash<H: Hasher>(&self, state: &mut H) {
         let poly_expr = Expr::from(self.monomials.clone());
@@ -258,37 +332,39 @@ impl Hash for Poly {
     }
 }
 
-impl Display for Poly {
+impl<C: Coefficient> Display for Poly<C> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.e)
+        write!(f, "{}", self.pretty(PrintStyle::Ascii))
     }
 }
 
-impl Index<usize> for Poly {
-    type Output = Mono;
+impl<C: Coefficient> Index<usize> for Poly<C> {
+    type Output = Mono<C>;
 
     fn index(&self, index: usize) -> &Self::Output {
         self.monomials.values().collect::<Vec<_>>()[index]
     }
 }
 
-impl IndexMut<usize> for Poly {
+impl<C: Coefficient> IndexMut<usize> for Poly<C> {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
         self.monomials.values_mut().nth(index).unwrap()
     }
 }
 
-impl PartialEq for Poly {
+impl<C: Coefficient> PartialEq for Poly<C> {
+    // the Expr view holds approximate coefficients, so the exact ones are compared too
     fn eq(&self, other: &Self) -> bool {
-        let _self_exp = Expr::from(self.monomials.clone());
-        let _other_exp = Expr::from(other.monomials.clone());
-        return self.e == other.e;
+        let coefficients = |p: &Poly<C>| -> BTreeMap<String, C> {
+            return p.monomials.values().map(|m| (mono_key(m.variables()), m.coefficient().clone())).collect();
+        };
+        return self.e == other.e && coefficients(self) == coefficients(other);
     }
 }
 
-impl Eq for Poly {}
+impl<C: Coefficient> Eq for Poly<C> {}
 
-impl Clone for Poly {
+impl<C: Coefficient> Clone for Poly<C> {
     fn clone(&self) -> Self {
         Poly {
             ORDER: self.ORDER.clone(),
@@ -299,229 +375,124 @@ impl Clone for Poly {
 }
 
 // implementations of multiplication operator for Poly
-impl Mul<Mono> for Poly {
-    type Output = Poly; 
-
-    fn mul(self, other: Mono) -> Self::Output {
-        let mut return_poly = self.clone();
-        let other  = Mono::from(other);
-        for mono in return_poly.monomials.values_mut() {
-            *mono = mono.mul(&other).unwrap_or_else(|_| mono.clone());
-        }
-        return_poly.update_expr(); // update expr after multiplication
-        return self;
+impl<C: Coefficient> Mul<Mono<C>> for Poly<C> {
+    type Output = Poly<C>;
+
+    fn mul(self, other: Mono<C>) -> Self::Output {
+        let mut return_poly = self.zero_like();
+        return_poly.extend(self.monomials.values().map(|mono| mono * &other));
+        return return_poly;
     }
 }
 
-impl Mul<Poly> for Poly {
-    type Output = Poly; 
+impl<C: Coefficient> Mul<Poly<C>> for Poly<C> {
+    type Output = Poly<C>;
 
-    fn mul(self, other: Poly) -> Self::Output {
-        let mut return_poly = self.clone();
-        for mono1 in return_poly.monomials.values_mut() {
-            for mono2 in other.monomials.values() {
-                *mono1 = mono1.mul(mono2).unwrap_or_else(|_| mono1.clone());
-            }
-        }
-        return_poly.update_expr(); // update expr after multiplication
-        return self;
+    fn mul(self, other: Poly<C>) -> Self::Output {
+        let mut return_poly = self.zero_like();
+        return_poly.extend(self.monomials.values().flat_map(|mono1| other.monomials.values().map(move |mono2| mono1 * mono2)));
+        return return_poly;
     }
 }
 
-impl Add<Mono> for Poly {
-    type Output = Poly;
-    fn add(self, rhs: Mono) -> Self::Output {
+impl<C: Coefficient> Add<Mono<C>> for Poly<C> {
+    type Output = Poly<C>;
+    fn add(self, rhs: Mono<C>) -> Self::Output {
         let mut result_poly = self;
-        let mut common_mono: Option<&Mono> = None;
-        for mono in result_poly.monomials.values_mut() {
-            if mono.common_term(&rhs) {
-                common_mono = Some(mono);
-            }                
-        }
-
-        match common_mono {
-            Some(val) => {
-                let mut coeff = val.coefficient_mut();
-                coeff += rhs.coefficient().clone(); // be careful no setter used here
-                let e: Expr = Expr::from(result_poly.monomials.clone());
-                result_poly.e = e;
-                result_poly
-            }
-            None => {
-                result_poly.monomials.insert(rhs.to_string(), rhs);
-                let e: Expr = Expr::from(result_poly.monomials.clone());
-                result_poly.e = e;
-                result_poly
-            }
-        }
-        
+        result_poly.insert_mono(rhs);
+        result_poly
     }
 }
 
 // implementations of addition operator for Poly
-impl Add for Poly {
-    type Output = Poly;
+impl<C: Coefficient> Add for Poly<C> {
+    type Output = Poly<C>;
 
     fn add(self, other: Self) -> Self::Output {
-        let mut result_poly = self.clone();
-        for (_, mono) in other.monomials {
-            result_poly = result_poly + mono;
-        }
+        let mut result_poly = self;
+        result_poly.extend(other.monomials.into_values());
         result_poly
     }
 }
 
+fn negate<C: Coefficient>(mono: Mono<C>) -> Mono<C> {
+    return Mono::from((-mono.coefficient().clone(), mono.variables().clone()));
+}
+
 // implementations of subtraction operator for Poly, Mono
-impl Sub<Mono> for Poly {
-    type Output = Poly;
-    fn sub(self, rhs: Mono) -> Self::Output {
+impl<C: Coefficient> Sub<Mono<C>> for Poly<C> {
+    type Output = Poly<C>;
+    fn sub(self, rhs: Mono<C>) -> Self::Output {
         let mut result_poly = self;
-        let mut common_mono: Option<&Mono> = None;
-        for mono in result_poly.monomials.values_mut() {
-            if mono.common_term(&rhs) {
-                common_mono = Some(mono);
-            }                
-        }
-
-        match common_mono {
-            Some(val) => {
-                let mut coeff = val.coefficient_mut();
-                coeff += rhs.coefficient().clone(); // be careful no setter used here
-                let e: Expr = Expr::from(result_poly.monomials.clone());
-                result_poly.e = e;
-                result_poly
-            }
-            None => {
-                result_poly.monomials.insert(rhs.to_string(), rhs);
-                let e: Expr = Expr::from(result_poly.monomials.clone());
-                result_poly.e = e;
-                result_poly
-            }
-        }
-        
+        result_poly.insert_mono(negate(rhs));
+        result_poly
     }
 }
 
 // implementations of subtraction operator for Poly, Poly
-impl Sub for Poly {
-    type Output = Poly;
+impl<C: Coefficient> Sub for Poly<C> {
+    type Output = Poly<C>;
 
     fn sub(self, other: Self) -> Self::Output {
-        let mut result_poly = self.clone();
-        for (_, mono) in other.monomials {
-            result_poly = result_poly - mono;
-        }
+        let mut result_poly = self;
+        result_poly.extend(other.monomials.into_values().map(negate));
         result_poly
     }
 }
 
 // implementations of division operator for Poly
-impl Div for Poly {
-    type Output = (Poly, Poly);
-
-    fn div(self, other: Poly) -> Self::Output {
-        let mut other_vec: Vec<Poly> = Vec::new();
-        other_vec.push(other);
-
-        let mut p = self.clone();
-        let mut g = other_vec.clone();
-        let mut q = Poly::default();
-        let mut r = Poly::default();
-
-        let mut i: i32 = -1;
-        while  !p.zero() {
-            i += 1;
-            let i = usize::try_from(i).expect("Failed to convert: value is negative or too large");
-
-            // value of i is guarranteed to be usize for indexing
-            let test = g[i].leading_term().cofactor(p.leading_term()).clone();
-            match test.clone() {
-                Ok(_value) => {
-                    let lt_mult: Mono = p.leading_term().clone().div(g[i].leading_term().clone()).unwrap();
-                    p = p - (g[i].clone() * Poly::from(lt_mult.clone()));
-                    q = q + Poly::from(lt_mult.clone());
-                }
-                Err(_error) => ()
-            }
-
-            // now value of i is guarranteed to be i32 for subtraction
-            let mut i: i32 = i32::try_from(i).expect("Failed to convert: value is negative or too large");
-            match test.clone() {
-                Ok(_value) => {
-                    i = -1;
-                }
-                Err(_error) => ()
-            }
+impl<C: Coefficient> Div for Poly<C> {
+    type Output = (Poly<C>, Poly<C>);
 
-            if i == ((other_vec.len() - 1) as i32) {
-                let lt_p: Mono = p.leading_term().clone();
-                r = r + Poly::from(lt_p.clone());
-                p = p - Poly::from(lt_p.clone());
-                i = -1;
-            }
-        }
-        return (q, r);
+    fn div(self, other: Poly<C>) -> Self::Output {
+        return self / vec![other];
     }
 }
 
-impl Div<Vec<Poly>> for Poly {
-    type Output = (Poly, Poly);
+// multivariate division: the leading term of p is cancelled by the first divisor whose
+// leading term divides it, otherwise it is moved to the remainder
+impl<C: Coefficient> Div<Vec<Poly<C>>> for Poly<C> {
+    type Output = (Poly<C>, Poly<C>);
 
-    fn div(self, other: Vec<Poly>) -> Self::Output {
+    fn div(self, other: Vec<Poly<C>>) -> Self::Output {
         let mut p = self.clone();
-        let mut g = other.clone();
-        let mut q = Poly::default();
-        let mut r = Poly::default();
-
-        let mut i: i32 = -1;
-        while  !p.zero() {
-            i += 1;
-            let i = usize::try_from(i).expect("Failed to convert: value is negative or too large");
-
-            // value of i is guarranteed to be usize for indexing
-            let test = g[i].leading_term().cofactor(p.leading_term()).clone();
-            match test.clone() {
-                Ok(_value) => {
-                    let lt_mult: Mono = p.leading_term().clone().div(g[i].leading_term().clone()).unwrap();
-                    p = p - (g[i].clone() * Poly::from(lt_mult.clone()));
-                    q = q + Poly::from(lt_mult.clone());
+        let mut g: Vec<Poly<C>> = other.into_iter().filter(|d| !d.monomials.is_empty()).collect();
+        for d in g.iter_mut() {
+            d.ORDER = self.ORDER.clone();
+        }
+        let mut q = self.zero_like();
+        let mut r = self.zero_like();
+
+        while !p.zero() {
+            let lt_p: Mono<C> = p.leading_term().clone();
+            let mut divided: bool = false;
+            for d in g.iter_mut() {
+                if let Ok(m) = d.leading_term().cofactor(&lt_p) {
+                    p = p - (d.clone() * m.clone());
+                    q = q + m;
+                    divided = true;
+                    break;
                 }
-                Err(_error) => ()
             }
-
-            // now value of i is guarranteed to be i32 for subtraction
-            let mut i: i32 = i32::try_from(i).expect("Failed to convert: value is negative or too large");
-            match test.clone() {
-                Ok(_value) => {
-                    i = -1;
-                }
-                Err(_error) => ()
-            }
-
-            if i == ((other.len() - 1) as i32) {
-                let lt_p: Mono = p.leading_term().clone();
-                r = r + Poly::from(lt_p.clone());
-                p = p - Poly::from(lt_p.clone());
-                i = -1;
+            if !divided {
+                r = r + lt_p.clone();
+                p = p - lt_p;
             }
         }
         return (q, r);
     }
 }
 
-impl Div<Mono> for Poly {
-    type Output = Poly;
+// termwise, fails like `Mono` division when a coefficient cannot be divided
+impl<C: Coefficient> Div<Mono<C>> for Poly<C> {
+    type Output = Result<Poly<C>, bool>;
 
-    fn div(self, other: Mono) -> Self::Output {
-        let mut result_poly = self;
-        let mut updated_monomials = BTreeMap::new();
-        for (key, mono) in result_poly.monomials.iter() {
+    fn div(self, other: Mono<C>) -> Self::Output {
+        let mut result_poly = self.zero_like();
+        for mono in self.monomials.values() {
             let (new_mono, other_val) = mono.configure(&other);
-            let divided_mono = new_mono.div(other_val).unwrap();
-            updated_monomials.insert(key.clone(), divided_mono);
+            result_poly.extend([new_mono.div(other_val)?]);
         }
-        result_poly.monomials = updated_monomials;
-        result_poly.update_expr();
-        result_poly
+        return Ok(result_poly);
     }
 }
\ No newline at end of file
@@ -0,0 +1,66 @@
+use num_notation::*;
+use std::collections::*;
+
+use crate::mono::mono::*;
+use crate::expr::expr::*;
+use crate::poly::poly::Poly;
+use crate::poly::expand::{natural, power, MAX_EXPONENT};
+
+// Substitution into polynomials.
+//
+// `subs` replaces variables by polynomial expressions and stays inside `Poly`,
+// `partial_eval` fixes some variables to numbers and keeps a polynomial in
+// the others.
+
+impl Poly {
+    // replaces variables by expressions, `None` unless every value used is a
+    // polynomial and every substituted exponent a natural number up to `MAX_EXPONENT`
+    pub fn subs(&self, values: &BTreeMap<String, Expr>) -> Option<Poly> {
+        let mut polys: BTreeMap<String, Poly> = BTreeMap::new();
+        for (s, value) in values.iter() {
+            polys.insert(s.clone(), Poly::try_from(value).ok()?);
+        }
+        let mut result: Poly = self.zero_like();
+        for mono in self.monomials.values() {
+            let mut kept: Variables = Variables::new();
+            let mut factors: Vec<Poly> = Vec::new();
+            for (s, n) in mono.variables().iter() {
+                match polys.get(s) {
+                    Some(p) => factors.push(power(p, natural(n).filter(|n| *n <= MAX_EXPONENT)?)),
+                    None => {
+                        kept.insert(s.clone(), n.clone());
+                    }
+                }
+            }
+            let mut term: Poly = self.zero_like();
+            term.insert_mono(Mono::from((mono.coefficient().clone(), kept)));
+            for factor in factors.into_iter() {
+                term = term * factor;
+            }
+            result = result + term;
+        }
+        return Some(result);
+    }
+
+    // evaluates the variables given in `values` and keeps the others, e.g.
+    // x^2*y + x at x = 2 is 4*y + 2
+    pub fn partial_eval(&self, values: &Variables) -> Poly {
+        let mut monos: Vec<Mono> = Vec::new();
+        for mono in self.monomials.values() {
+            let mut coefficient: f64 = f64::from(mono.coefficient().clone());
+            let mut kept: Variables = Variables::new();
+            for (s, n) in mono.variables().iter() {
+                match values.get(s) {
+                    Some(val) => coefficient *= f64::from(val.clone()).powf(f64::from(n.clone())),
+                    None => {
+                        kept.insert(s.clone(), n.clone());
+                    }
+                }
+            }
+            monos.push(Mono::from((Number::Decimal(coefficient), kept)));
+        }
+        let mut result: Poly = self.zero_like();
+        result.extend(monos);
+        return result;
+    }
+}
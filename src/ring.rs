@@ -1 +1,8 @@
-pub mod ring;
\ No newline at end of file
+// lints the original module trips, such as the S-polynomial's `L`
+#[allow(non_snake_case)]
+#[allow(clippy::module_inception, clippy::redundant_field_names)]
+pub mod ring;
+pub mod modular;
+pub mod buchberger;
+pub mod f4;
+pub mod ideal;
\ No newline at end of file
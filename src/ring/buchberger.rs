@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+use std::collections::*;
+use num_notation::Number;
+
+use crate::coefficient::coefficient::Coefficient;
+use crate::expr::expr::Variables;
+use crate::mono::order::Order;
+use crate::poly::poly::Poly;
+use crate::ring::ring::Ring;
+
+// Buchberger's algorithm.
+//
+// Critical pairs wait in a queue and are discarded by the criteria of
+// Gebauer and Möller when a new element joins the basis: the product
+// criterion drops pairs with coprime leading monomials, the chain criterion
+// drops pairs whose lcm is a multiple of the leading monomial of a third
+// element that already pairs with both. The remaining pairs are taken with
+// the smallest lcm first (normal selection) or the smallest sugar degree
+// first, and each S-polynomial is reduced by the current basis. Elements
+// whose leading monomial becomes a multiple of a newer one stop reducing but
+// keep their pairs. At the end the basis is interreduced, made monic and
+// sorted by leading monomial, smallest first, which makes it unique.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Selection {
+    #[default]
+    Normal,
+    Sugar,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GrobnerStats {
+    // pairs formed, whether reduced or discarded
+    pub pairs: usize,
+    pub product_criterion: usize,
+    pub chain_criterion: usize,
+    // S-polynomials reduced, and how many of them went to zero
+    pub reductions: usize,
+    pub zero_reductions: usize,
+}
+
+pub(crate) struct Pair {
+    pub(crate) i: usize,
+    pub(crate) j: usize,
+    pub(crate) lcm: Variables,
+    pub(crate) sugar: f64,
+}
+
+fn exponent(v: &Variables, k: &String) -> f64 {
+    match v.get(k) {
+        Some(n) => f64::from(n.clone()),
+        None => 0.0,
+    }
+}
+
+pub(crate) fn degree(v: &Variables) -> f64 {
+    return v.values().map(|n| f64::from(n.clone())).sum();
+}
+
+fn lcm(a: &Variables, b: &Variables) -> Variables {
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    return keys.into_iter()
+        .map(|k| (k.clone(), Number::Decimal(exponent(a, k).max(exponent(b, k)))))
+        .filter(|(_, n)| *n != 0.0)
+        .collect();
+}
+
+// a divides b
+fn divides(a: &Variables, b: &Variables) -> bool {
+    return a.iter().all(|(k, n)| f64::from(n.clone()) <= exponent(b, k));
+}
+
+fn coprime(a: &Variables, b: &Variables) -> bool {
+    return a.iter().all(|(k, n)| *n == 0.0 || exponent(b, k) == 0.0);
+}
+
+// leading monomial without zero exponents
+pub(crate) fn leading_monomial<C: Coefficient>(p: &Poly<C>) -> Variables {
+    return p.sorted_monomials()[0].variables().iter()
+        .filter(|(_, n)| **n != 0.0)
+        .map(|(k, n)| (k.clone(), n.clone()))
+        .collect();
+}
+
+pub(crate) fn total_degree<C: Coefficient>(p: &Poly<C>) -> f64 {
+    return p.monomials.values().map(|m| degree(m.variables())).fold(0.0, f64::max);
+}
+
+// the basis sorted by leading monomial, smallest first
+pub(crate) fn sort_basis<C: Coefficient>(basis: &mut [Poly<C>], order: &Order) {
+    basis.sort_by(|a, b| order.compare(&leading_monomial(a), &leading_monomial(b)));
+}
+
+// every element reduced by the others and made monic, then sorted; `basis` has to be minimal
+pub(crate) fn interreduce<C: Coefficient>(mut basis: Vec<Poly<C>>, order: &Order) -> Vec<Poly<C>> {
+    for i in 0..basis.len() {
+        let mut others: Vec<Poly<C>> = basis.clone();
+        let f: Poly<C> = others.remove(i);
+        let (_, r): (Poly<C>, Poly<C>) = f / others;
+        basis[i] = r.monic();
+    }
+    sort_basis(&mut basis, order);
+    return basis;
+}
+
+// the critical pairs of a growing basis, by index into its elements
+pub(crate) struct CriticalPairs {
+    order: Order,
+    selection: Selection,
+    leads: Vec<Variables>,
+    sugars: Vec<f64>,
+    active: Vec<bool>,
+    pairs: Vec<Pair>,
+    pub(crate) stats: GrobnerStats,
+}
+
+impl CriticalPairs {
+    pub(crate) fn new(order: Order, selection: Selection) -> CriticalPairs {
+        return CriticalPairs {
+            order,
+            selection,
+            leads: Vec::new(),
+            sugars: Vec::new(),
+            active: Vec::new(),
+            pairs: Vec::new(),
+            stats: GrobnerStats::default(),
+        };
+    }
+
+    fn pair(&self, i: usize, j: usize) -> Pair {
+        let l: Variables = lcm(&self.leads[i], &self.leads[j]);
+        let sugar: f64 = (self.sugars[i] + degree(&l) - degree(&self.leads[i]))
+            .max(self.sugars[j] + degree(&l) - degree(&self.leads[j]));
+        return Pair { i, j, lcm: l, sugar };
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        return self.pairs.is_empty();
+    }
+
+    // indices of the elements that still reduce
+    pub(crate) fn active(&self) -> Vec<usize> {
+        return (0..self.active.len()).filter(|i| self.active[*i]).collect();
+    }
+
+    // Gebauer–Möller update for a new element with leading monomial `h_lead`, returns its index
+    pub(crate) fn insert(&mut self, h_lead: Variables, sugar: f64) -> usize {
+        let k: usize = self.leads.len();
+        self.leads.push(h_lead.clone());
+        self.sugars.push(sugar);
+        self.active.push(true);
+
+        let mut candidates: Vec<Pair> = (0..k).filter(|i| self.active[*i]).map(|i| self.pair(i, k)).collect();
+        self.stats.pairs += candidates.len();
+
+        // chain criterion among the new pairs: keep (g, h) when the lcm is not a
+        // multiple of another new lcm, and always when the leading monomials are coprime
+        let mut kept: Vec<Pair> = Vec::new();
+        while let Some(p) = candidates.pop() {
+            let redundant: bool = !coprime(&self.leads[p.i], &h_lead)
+                && candidates.iter().chain(kept.iter()).any(|q| divides(&q.lcm, &p.lcm));
+            if redundant {
+                self.stats.chain_criterion += 1;
+            }
+            else {
+                kept.push(p);
+            }
+        }
+        // product criterion
+        let before: usize = kept.len();
+        kept.retain(|p| !coprime(&self.leads[p.i], &h_lead));
+        self.stats.product_criterion += before - kept.len();
+
+        // chain criterion on the old pairs, with h as the third element
+        let (leads, pairs) = (&self.leads, std::mem::take(&mut self.pairs));
+        let mut old: Vec<Pair> = Vec::new();
+        for p in pairs.into_iter() {
+            let redundant: bool = divides(&h_lead, &p.lcm)
+                && lcm(&leads[p.i], &h_lead) != p.lcm
+                && lcm(&leads[p.j], &h_lead) != p.lcm;
+            if redundant {
+                self.stats.chain_criterion += 1;
+            }
+            else {
+                old.push(p);
+            }
+        }
+        old.extend(kept);
+        self.pairs = old;
+
+        for i in 0..k {
+            if self.active[i] && divides(&h_lead, &self.leads[i]) {
+                self.active[i] = false;
+            }
+        }
+        return k;
+    }
+
+    pub(crate) fn select(&mut self) -> Pair {
+        let order: &Order = &self.order;
+        let by_lcm = |a: &Pair, b: &Pair| -> Ordering { order.compare(&a.lcm, &b.lcm) };
+        let best: usize = match self.selection {
+            Selection::Normal => (0..self.pairs.len())
+                .min_by(|a, b| by_lcm(&self.pairs[*a], &self.pairs[*b]))
+                .unwrap(),
+            Selection::Sugar => (0..self.pairs.len())
+                .min_by(|a, b| self.pairs[*a].sugar.total_cmp(&self.pairs[*b].sugar)
+                    .then_with(|| by_lcm(&self.pairs[*a], &self.pairs[*b])))
+                .unwrap(),
+        };
+        return self.pairs.swap_remove(best);
+    }
+
+    // every pair of the lowest lcm degree, or sugar degree under `Selection::Sugar`
+    pub(crate) fn select_batch(&mut self) -> Vec<Pair> {
+        let key = |p: &Pair, selection: Selection| -> f64 {
+            match selection {
+                Selection::Normal => degree(&p.lcm),
+                Selection::Sugar => p.sugar,
+            }
+        };
+        let lowest: f64 = self.pairs.iter().map(|p| key(p, self.selection)).fold(f64::INFINITY, f64::min);
+        let (batch, rest): (Vec<Pair>, Vec<Pair>) = std::mem::take(&mut self.pairs).into_iter()
+            .partition(|p| key(p, self.selection) == lowest);
+        self.pairs = rest;
+        return batch;
+    }
+}
+
+struct Buchberger<C: Coefficient> {
+    polys: Vec<Poly<C>>,
+    pairs: CriticalPairs,
+}
+
+impl<C: Coefficient> Buchberger<C> {
+    fn update(&mut self, h: Poly<C>, sugar: f64) {
+        self.pairs.insert(leading_monomial(&h), sugar);
+        self.polys.push(h);
+    }
+
+    fn reducers(&self) -> Vec<Poly<C>> {
+        return self.pairs.active().into_iter().map(|i| self.polys[i].clone()).collect();
+    }
+
+    fn run(&mut self) {
+        while !self.pairs.is_empty() {
+            let pair: Pair = self.pairs.select();
+            let s: Poly<C> = Ring::s_poly(self.polys[pair.i].clone(), self.polys[pair.j].clone());
+            let (_, mut r): (Poly<C>, Poly<C>) = s / self.reducers();
+            self.pairs.stats.reductions += 1;
+            if r.zero() {
+                self.pairs.stats.zero_reductions += 1;
+            }
+            else {
+                self.update(r.monic(), pair.sugar);
+            }
+        }
+    }
+}
+
+impl<C: Coefficient> Ring<C> {
+    // the reduced Gröbner basis of `set_poly` under the order of its first polynomial,
+    // monic and sorted by leading monomial
+    pub fn buchberger(set_poly: HashSet<Poly<C>>, selection: Selection) -> (Vec<Poly<C>>, GrobnerStats) {
+        let mut input: Vec<Poly<C>> = set_poly.into_iter().filter(|p| !p.monomials.is_empty()).collect();
+        let order: Order = match input.first() {
+            Some(p) => p.order(),
+            None => return (Vec::new(), GrobnerStats::default()),
+        };
+        input = input.into_iter().map(|p| p.with_order(order.clone()).monic()).collect();
+        sort_basis(&mut input, &order);
+
+        let mut state: Buchberger<C> = Buchberger {
+            polys: Vec::new(),
+            pairs: CriticalPairs::new(order.clone(), selection),
+        };
+        for p in input.into_iter() {
+            // inputs are reduced by the earlier ones, as if they came out of a pair
+            let (_, mut r): (Poly<C>, Poly<C>) = p.clone() / state.reducers();
+            if !r.zero() {
+                state.update(r.monic(), total_degree(&p));
+            }
+        }
+        state.run();
+
+        let basis: Vec<Poly<C>> = state.reducers();
+        return (interreduce(basis, &order), state.pairs.stats);
+    }
+
+    // `buchberger` under the order of the ring
+    pub fn grobner(&self, set_poly: HashSet<Poly<C>>, selection: Selection) -> (Vec<Poly<C>>, GrobnerStats) {
+        return Ring::buchberger(self.configure(set_poly), selection);
+    }
+}
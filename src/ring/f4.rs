@@ -0,0 +1,270 @@
+use std::cmp::Ordering;
+use std::collections::*;
+use num_notation::Number;
+
+use crate::coefficient::coefficient::Coefficient;
+use crate::expr::expr::Variables;
+use crate::mono::mono::Mono;
+use crate::mono::order::Order;
+use crate::poly::poly::Poly;
+use crate::ring::buchberger::*;
+use crate::ring::ring::Ring;
+
+// F4.
+//
+// The same critical pairs as `Ring::buchberger`, with the same criteria,
+// but all pairs of the lowest degree are reduced together. Both halves of
+// each pair, lcm/lt(f) * f and lcm/lt(g) * g, become rows of a Macaulay
+// matrix, and symbolic preprocessing adds a multiple of a basis element for
+// every monomial of the matrix that some leading monomial divides. Sparse
+// row echelon form over the coefficient field then leaves new basis
+// elements in the rows whose leading monomial no row started with. The
+// basis is interreduced like Buchberger's, so the two agree exactly.
+//
+// Polynomials are kept as terms with exponent vectors over the variables of
+// the input, sorted greatest first, so only natural exponents are allowed.
+// Prime fields are the intended domain; any field works.
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct F4Stats {
+    pub pairs: usize,
+    pub product_criterion: usize,
+    pub chain_criterion: usize,
+    // pairs sent to a matrix
+    pub reduced_pairs: usize,
+    pub matrices: usize,
+    pub rows: usize,
+    // rows and columns of the largest matrix
+    pub largest: (usize, usize),
+    pub new_elements: usize,
+}
+
+type Exponents = Vec<u32>;
+
+// terms greatest first
+type Terms<C> = Vec<(Exponents, C)>;
+
+// (column, coefficient), columns ascending, column 0 the greatest monomial
+type Row<C> = Vec<(usize, C)>;
+
+struct F4<C: Coefficient> {
+    variables: Vec<String>,
+    order: Order,
+    polys: Vec<Terms<C>>,
+    pairs: CriticalPairs,
+    stats: F4Stats,
+}
+
+fn divides(a: &[u32], b: &[u32]) -> bool {
+    return a.iter().zip(b.iter()).all(|(x, y)| x <= y);
+}
+
+fn quotient(a: &[u32], b: &[u32]) -> Exponents {
+    return a.iter().zip(b.iter()).map(|(x, y)| x - y).collect();
+}
+
+fn times<C: Coefficient>(m: &[u32], terms: &Terms<C>) -> Terms<C> {
+    return terms.iter()
+        .map(|(e, c)| (e.iter().zip(m.iter()).map(|(x, y)| x + y).collect(), c.clone()))
+        .collect();
+}
+
+// a - c*b for rows sorted by column
+fn subtract<C: Coefficient>(a: &Row<C>, c: &C, b: &Row<C>) -> Row<C> {
+    let mut result: Row<C> = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if j == b.len() || (i < a.len() && a[i].0 < b[j].0) {
+            result.push(a[i].clone());
+            i += 1;
+        }
+        else if i == a.len() || b[j].0 < a[i].0 {
+            result.push((b[j].0, -(c.clone() * b[j].1.clone())));
+            j += 1;
+        }
+        else {
+            let value: C = a[i].1.clone() - c.clone() * b[j].1.clone();
+            if !value.is_zero() {
+                result.push((a[i].0, value));
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+    return result;
+}
+
+impl<C: Coefficient> F4<C> {
+    fn variables_of(&self, e: &[u32]) -> Variables {
+        return self.variables.iter().zip(e.iter())
+            .filter(|(_, n)| **n != 0)
+            .map(|(s, n)| (s.clone(), Number::Decimal(*n as f64)))
+            .collect();
+    }
+
+    fn compare(&self, a: &[u32], b: &[u32]) -> Ordering {
+        return self.order.compare(&self.variables_of(a), &self.variables_of(b));
+    }
+
+    fn exponents(&self, v: &Variables) -> Exponents {
+        return self.variables.iter().map(|s| {
+            let n: f64 = v.get(s).map_or(0.0, |n| f64::from(n.clone()));
+            assert!(n >= 0.0 && n.fract() == 0.0, "F4 needs natural exponents, found {}^{}", s, n);
+            return n as u32;
+        }).collect();
+    }
+
+    fn terms(&self, p: &Poly<C>) -> Terms<C> {
+        let mut terms: Terms<C> = p.monomials.values()
+            .map(|m| (self.exponents(m.variables()), m.coefficient().clone()))
+            .collect();
+        terms.sort_by(|a, b| self.compare(&b.0, &a.0));
+        return terms;
+    }
+
+    fn poly(&self, terms: &Terms<C>) -> Poly<C> {
+        let mut p: Poly<C> = Poly { ORDER: self.order.clone(), ..Poly::default() };
+        p.extend(terms.iter().map(|(e, c)| Mono::from((c.clone(), self.variables_of(e)))));
+        return p;
+    }
+
+    fn insert(&mut self, terms: Terms<C>, sugar: f64) {
+        self.pairs.insert(self.variables_of(&terms[0].0), sugar);
+        self.polys.push(terms);
+    }
+
+    // the rows of a batch of pairs, completed by symbolic preprocessing
+    fn preprocess(&self, batch: &[Pair]) -> Vec<Terms<C>> {
+        let mut rows: Vec<Terms<C>> = Vec::new();
+        let mut products: HashSet<(usize, Exponents)> = HashSet::new();
+        for pair in batch.iter() {
+            let lcm: Exponents = self.exponents(&pair.lcm);
+            for k in [pair.i, pair.j] {
+                let m: Exponents = quotient(&lcm, &self.polys[k][0].0);
+                if products.insert((k, m.clone())) {
+                    rows.push(times(&m, &self.polys[k]));
+                }
+            }
+        }
+
+        let mut seen: HashSet<Exponents> = rows.iter().flat_map(|r| r.iter().map(|(e, _)| e.clone())).collect();
+        let done: HashSet<Exponents> = rows.iter().map(|r| r[0].0.clone()).collect();
+        let mut todo: Vec<Exponents> = seen.iter().filter(|e| !done.contains(*e)).cloned().collect();
+        let reducers: Vec<usize> = self.pairs.active();
+        while let Some(m) = todo.pop() {
+            let reducer: Option<&usize> = reducers.iter().find(|k| divides(&self.polys[**k][0].0, &m));
+            if let Some(k) = reducer {
+                let row: Terms<C> = times(&quotient(&m, &self.polys[*k][0].0), &self.polys[*k]);
+                for (e, _) in row.iter().skip(1) {
+                    if seen.insert(e.clone()) {
+                        todo.push(e.clone());
+                    }
+                }
+                rows.push(row);
+            }
+        }
+        return rows;
+    }
+
+    // sparse row echelon form, returns the rows with a leading monomial no input row had
+    fn reduce(&mut self, rows: Vec<Terms<C>>) -> Vec<Terms<C>> {
+        let mut columns: Vec<Exponents> = rows.iter()
+            .flat_map(|r| r.iter().map(|(e, _)| e.clone()))
+            .collect::<HashSet<Exponents>>()
+            .into_iter()
+            .collect();
+        columns.sort_by(|a, b| self.compare(b, a));
+        let index: HashMap<&Exponents, usize> = columns.iter().enumerate().map(|(i, e)| (e, i)).collect();
+        self.stats.matrices += 1;
+        self.stats.rows += rows.len();
+        if rows.len() * columns.len() > self.stats.largest.0 * self.stats.largest.1 {
+            self.stats.largest = (rows.len(), columns.len());
+        }
+
+        let leading: HashSet<usize> = rows.iter().map(|r| index[&r[0].0]).collect();
+        let matrix: Vec<Row<C>> = rows.iter()
+            .map(|r| r.iter().map(|(e, c)| (index[e], c.clone())).collect())
+            .collect();
+        // each pivot row starts with a one
+        let mut pivots: BTreeMap<usize, Row<C>> = BTreeMap::new();
+        for mut row in matrix.into_iter() {
+            while let Some((column, c)) = row.first().cloned() {
+                match pivots.get(&column) {
+                    Some(pivot) => row = subtract(&row, &c, pivot),
+                    None => {
+                        let inverse: C = c.inverse().expect("F4 needs a field of coefficients");
+                        let row: Row<C> = row.into_iter().map(|(k, v)| (k, v * inverse.clone())).collect();
+                        pivots.insert(column, row);
+                        break;
+                    }
+                }
+            }
+        }
+
+        return pivots.into_iter()
+            .filter(|(column, _)| !leading.contains(column))
+            .map(|(_, row)| row.into_iter().map(|(k, c)| (columns[k].clone(), c)).collect())
+            .collect();
+    }
+
+    fn run(&mut self) {
+        while !self.pairs.is_empty() {
+            let batch: Vec<Pair> = self.pairs.select_batch();
+            let sugar: f64 = batch.iter().map(|p| p.sugar).fold(0.0, f64::max);
+            self.stats.reduced_pairs += batch.len();
+            let rows: Vec<Terms<C>> = self.preprocess(&batch);
+            let mut found: Vec<Terms<C>> = self.reduce(rows);
+            // greatest first, so an element whose leading monomial is a multiple
+            // of a later one is retired by it and the active elements stay minimal
+            found.sort_by(|a, b| self.compare(&b[0].0, &a[0].0));
+            self.stats.new_elements += found.len();
+            for terms in found.into_iter() {
+                self.insert(terms, sugar);
+            }
+        }
+    }
+}
+
+impl<C: Coefficient> Ring<C> {
+    // the reduced Gröbner basis of `set_poly` by F4, equal to the one of `Ring::buchberger`;
+    // panics on exponents that are not natural numbers and on a domain that is not a field
+    pub fn f4(set_poly: HashSet<Poly<C>>, selection: Selection) -> (Vec<Poly<C>>, F4Stats) {
+        let mut input: Vec<Poly<C>> = set_poly.into_iter().filter(|p| !p.monomials.is_empty()).collect();
+        let order: Order = match input.first() {
+            Some(p) => p.order(),
+            None => return (Vec::new(), F4Stats::default()),
+        };
+        input = input.into_iter().map(|p| p.with_order(order.clone()).monic()).collect();
+        sort_basis(&mut input, &order);
+        let variables: BTreeSet<String> = input.iter()
+            .flat_map(|p| p.monomials.values().flat_map(|m| m.variables().keys().cloned()).collect::<Vec<_>>())
+            .collect();
+
+        let mut state: F4<C> = F4 {
+            variables: variables.into_iter().collect(),
+            order: order.clone(),
+            polys: Vec::new(),
+            pairs: CriticalPairs::new(order.clone(), selection),
+            stats: F4Stats::default(),
+        };
+        for p in input.iter().rev() {
+            let terms: Terms<C> = state.terms(p);
+            state.insert(terms, total_degree(p));
+        }
+        state.run();
+
+        let criteria: &GrobnerStats = &state.pairs.stats;
+        state.stats.pairs = criteria.pairs;
+        state.stats.product_criterion = criteria.product_criterion;
+        state.stats.chain_criterion = criteria.chain_criterion;
+
+        // a minimal basis, then interreduced as in Buchberger
+        let basis: Vec<Poly<C>> = state.pairs.active().into_iter().map(|k| state.poly(&state.polys[k])).collect();
+        return (interreduce(basis, &order), state.stats);
+    }
+
+    // `f4` under the order of the ring
+    pub fn grobner_f4(&self, set_poly: HashSet<Poly<C>>, selection: Selection) -> (Vec<Poly<C>>, F4Stats) {
+        return Ring::f4(self.configure(set_poly), selection);
+    }
+}
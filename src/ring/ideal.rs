@@ -0,0 +1,122 @@
+use std::cell::OnceCell;
+use std::collections::*;
+use std::sync::Arc;
+use num_notation::Number;
+
+use crate::coefficient::coefficient::Coefficient;
+use crate::mono::order::Order;
+use crate::poly::poly::Poly;
+use crate::ring::buchberger::*;
+use crate::ring::ring::Ring;
+
+// Ideals of a polynomial ring.
+//
+// An ideal keeps its generators and the ring they live in. Its reduced
+// Gröbner basis under the order of the ring is computed the first time it is
+// needed and kept; `with_order` gives the same ideal under another order,
+// with a fresh cache. Membership is a zero normal form, equality compares
+// reduced bases when both ideals use the same order and falls back to
+// containment both ways when they do not.
+
+#[derive(Clone)]
+pub struct Ideal<C: Coefficient = Number> {
+    ring: Ring<C>,
+    generators: Vec<Poly<C>>,
+    basis: OnceCell<Vec<Poly<C>>>,
+}
+
+impl<C: Coefficient> Ring<C> {
+    // the ideal of this ring generated by `generators`
+    pub fn ideal(&self, generators: impl IntoIterator<Item = Poly<C>>) -> Ideal<C> {
+        return Ideal::new(self, generators);
+    }
+}
+
+impl<C: Coefficient> Ideal<C> {
+    pub fn new(ring: &Ring<C>, generators: impl IntoIterator<Item = Poly<C>>) -> Ideal<C> {
+        let mut ring: Ring<C> = ring.clone();
+        let generators: Vec<Poly<C>> = generators.into_iter().collect();
+        // the ring has to know every variable, e.g. for `is_zero_dimensional`
+        for p in generators.iter() {
+            for mono in p.monomials.values() {
+                ring.variables.extend(mono.variables().iter().filter(|(_, n)| **n != 0.0).map(|(s, _)| s.clone()));
+            }
+        }
+        return Ideal { ring, generators, basis: OnceCell::new() };
+    }
+
+    pub fn ring(&self) -> &Ring<C> {
+        return &self.ring;
+    }
+
+    pub fn generators(&self) -> &[Poly<C>] {
+        return &self.generators;
+    }
+
+    pub fn order(&self) -> Order {
+        return self.ring.order.clone();
+    }
+
+    // the same ideal under `order`, its basis not computed yet
+    pub fn with_order(&self, order: Order) -> Ideal<C> {
+        return Ideal {
+            ring: self.ring.clone().with_order(order),
+            generators: self.generators.clone(),
+            basis: OnceCell::new(),
+        };
+    }
+
+    // reduced Gröbner basis under the order of the ring, monic and sorted, computed once
+    pub fn basis(&self) -> &[Poly<C>] {
+        return self.basis.get_or_init(|| {
+            let set_poly: HashSet<Poly<C>> = self.generators.iter().cloned().collect();
+            let (basis, _) = self.ring.grobner(set_poly, Selection::Normal);
+            return basis;
+        });
+    }
+
+    // remainder of `p` on division by the basis, zero exactly for members
+    pub fn normal_form(&self, p: &Poly<C>) -> Poly<C> {
+        let p: Poly<C> = p.clone().with_order(self.ring.order.clone());
+        if p.monomials.is_empty() {
+            return p;
+        }
+        let (_, r): (Poly<C>, Poly<C>) = p / self.basis().to_vec();
+        return r;
+    }
+
+    pub fn contains(&self, p: &Poly<C>) -> bool {
+        return self.normal_form(p).zero();
+    }
+
+    pub fn is_subset(&self, other: &Ideal<C>) -> bool {
+        return self.generators.iter().all(|p| other.contains(p));
+    }
+
+    // the basis is {1}
+    pub fn is_whole_ring(&self) -> bool {
+        return self.basis().iter().any(|p| p.monomials.values().all(|m| m.variables().values().all(|n| *n == 0.0)));
+    }
+
+    // finitely many common zeros: every variable of the ring has a pure power among
+    // the leading monomials of the basis; false for the whole ring
+    pub fn is_zero_dimensional(&self) -> bool {
+        if self.is_whole_ring() {
+            return false;
+        }
+        let leads: Vec<BTreeMap<String, Number>> = self.basis().iter().map(leading_monomial).collect();
+        return self.ring.variables.iter()
+            .all(|x| leads.iter().any(|lead| lead.len() == 1 && lead.contains_key(x)));
+    }
+}
+
+impl<C: Coefficient> PartialEq for Ideal<C> {
+    fn eq(&self, other: &Self) -> bool {
+        if Arc::ptr_eq(&self.ring.order, &other.ring.order) {
+            return self.basis() == other.basis();
+        }
+        return self.is_subset(other) && other.is_subset(self);
+    }
+}
+
+impl<C: Coefficient> Eq for Ideal<C> {}
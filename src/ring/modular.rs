@@ -0,0 +1,333 @@
+use std::collections::*;
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+
+use crate::coefficient::coefficient::Coefficient;
+use crate::coefficient::modular::*;
+use crate::coefficient::rational::Rational;
+use crate::expr::expr::Variables;
+use crate::mono::mono::Mono;
+use crate::mono::order::Order;
+use crate::poly::poly::*;
+use crate::ring::ring::Ring;
+use crate::ring::buchberger::{leading_monomial, total_degree, CriticalPairs, Pair, Selection};
+
+// Multi-modular Gröbner bases over the rationals.
+//
+// The input is mapped to GF(p) for the primes in `PRIMES`, and the reduced
+// basis of each image is computed there, where coefficients stay one word.
+// Buchberger's algorithm runs with every polynomial carrying its cofactors,
+// the h_i with g = h_1 f_1 + ... + h_n f_n for the input f_i, so each basis
+// element comes with a representation in the input. Images with the same
+// leading monomials are combined coefficient by coefficient, cofactors
+// included, with the Chinese remainder theorem. Once rational reconstruction
+// returns the same basis and cofactors for two moduli in a row, the candidate
+// G is checked over Q:
+//
+// - every element of G equals the combination its cofactors give, so G lies
+//   in the ideal I of the input;
+// - every input polynomial reduces to zero modulo G, so I lies in the ideal
+//   of G;
+// - every S-polynomial of G reduces to zero modulo G, so G is a Gröbner basis.
+//
+// G is then a Gröbner basis of I, and it is reduced and monic because every
+// image is, so it is the reduced basis over Q. None of this computes a basis
+// over Q. An unlucky prime only ever joins the group of its own leading
+// monomials or fails the checks, primes dividing a denominator or a leading
+// coefficient of the input are skipped. Only when the primes run out before
+// a candidate is verified is the basis computed over Q.
+
+// monomial key -> (exponents, residue or lifted value) for each element of a
+// reduced basis, as sorted by `Ring::buchberger`
+type Image<T> = Vec<BTreeMap<String, (Variables, T)>>;
+
+struct Lift {
+    modulus: BigInt,
+    elements: Image<BigInt>,
+    candidate: Option<Vec<Poly<Rational>>>,
+}
+
+impl Poly<Rational> {
+    // the image in GF(P), None when P divides a denominator
+    pub fn reduce_mod<const P: u64>(&self) -> Option<Poly<GF<P>>> {
+        return self.map_coefficients(GF::<P>::from_rational);
+    }
+}
+
+fn leading_key<C: Coefficient>(p: &Poly<C>) -> String {
+    return mono_key(p.sorted_monomials()[0].variables());
+}
+
+// `poly` with the cofactors h of `poly = h[0] f[0] + ... + h[n-1] f[n-1]` for the input f
+#[derive(Clone)]
+struct Traced<C: Coefficient> {
+    poly: Poly<C>,
+    cofactors: Vec<Poly<C>>,
+}
+
+impl<C: Coefficient> Traced<C> {
+    fn input(f: &[Poly<C>], i: usize) -> Traced<C> {
+        let cofactors: Vec<Poly<C>> = (0..f.len()).map(|j| {
+            let mut h: Poly<C> = f[i].zero_like();
+            if j == i {
+                h.extend([Mono::from((C::one(), Variables::new()))]);
+            }
+            return h;
+        }).collect();
+        return Traced { poly: f[i].clone(), cofactors };
+    }
+
+    fn times(&self, m: &Mono<C>) -> Traced<C> {
+        return Traced {
+            poly: self.poly.clone() * m.clone(),
+            cofactors: self.cofactors.iter().map(|h| h.clone() * m.clone()).collect(),
+        };
+    }
+
+    fn minus(self, other: &Traced<C>) -> Traced<C> {
+        return Traced {
+            poly: self.poly - other.poly.clone(),
+            cofactors: self.cofactors.into_iter().zip(other.cofactors.iter()).map(|(a, b)| a - b.clone()).collect(),
+        };
+    }
+
+    // None for the zero polynomial
+    fn lead(&self) -> Option<Mono<C>> {
+        return self.poly.sorted_monomials().first().cloned();
+    }
+
+    fn monic(&self) -> Option<Traced<C>> {
+        let inverse: C = self.lead()?.coefficient().inverse()?;
+        return Some(self.times(&Mono::from((inverse, Variables::new()))));
+    }
+
+    // the remainder of `Poly / Vec<Poly>` with its cofactors, the part already
+    // moved to the remainder and the part left to reduce always add up to the
+    // combination of the cofactors
+    fn reduce(self, by: &[&Traced<C>]) -> Traced<C> {
+        let leads: Vec<Option<Mono<C>>> = by.iter().map(|d| d.lead()).collect();
+        let mut rest: Traced<C> = self;
+        let mut r: Poly<C> = rest.poly.zero_like();
+        while let Some(lt) = rest.lead() {
+            let divisor = by.iter().zip(leads.iter())
+                .find_map(|(d, lead)| lead.as_ref()?.cofactor(&lt).ok().map(|m| (d, m)));
+            match divisor {
+                Some((d, m)) => rest = rest.minus(&d.times(&m)),
+                None => {
+                    r = r + lt.clone();
+                    rest.poly = rest.poly - lt;
+                }
+            }
+        }
+        return Traced { poly: r, cofactors: rest.cofactors };
+    }
+}
+
+fn traced_s_poly<C: Coefficient>(a: &Traced<C>, b: &Traced<C>) -> Option<Traced<C>> {
+    let (lt_a, lt_b) = (a.lead()?, b.lead()?);
+    let l: Mono<C> = Mono::lcm(&lt_a, &lt_b);
+    return Some(a.times(&lt_a.cofactor(&l).ok()?).minus(&b.times(&lt_b.cofactor(&l).ok()?)));
+}
+
+fn reducers<'a, C: Coefficient>(polys: &'a [Traced<C>], pairs: &CriticalPairs) -> Vec<&'a Traced<C>> {
+    return pairs.active().into_iter().map(|i| &polys[i]).collect();
+}
+
+// `Ring::buchberger` on `input` with every element traced back to the input,
+// None when a leading coefficient is not a unit
+fn traced_buchberger<C: Coefficient>(input: &[Poly<C>], order: &Order) -> Option<Vec<Traced<C>>> {
+    let mut traced: Vec<Traced<C>> = Vec::new();
+    for i in 0..input.len() {
+        if !input[i].monomials.is_empty() {
+            traced.push(Traced::input(input, i).monic()?);
+        }
+    }
+    traced.sort_by(|a, b| order.compare(&leading_monomial(&a.poly), &leading_monomial(&b.poly)));
+
+    let mut polys: Vec<Traced<C>> = Vec::new();
+    let mut pairs: CriticalPairs = CriticalPairs::new(order.clone(), Selection::Normal);
+    for t in traced.into_iter() {
+        let sugar: f64 = total_degree(&t.poly);
+        let mut r: Traced<C> = t.reduce(&reducers(&polys, &pairs));
+        if !r.poly.zero() {
+            pairs.insert(leading_monomial(&r.poly), sugar);
+            polys.push(r.monic()?);
+        }
+    }
+    while !pairs.is_empty() {
+        let pair: Pair = pairs.select();
+        let s: Traced<C> = traced_s_poly(&polys[pair.i], &polys[pair.j])?;
+        let mut r: Traced<C> = s.reduce(&reducers(&polys, &pairs));
+        if !r.poly.zero() {
+            pairs.insert(leading_monomial(&r.poly), pair.sugar);
+            polys.push(r.monic()?);
+        }
+    }
+
+    // interreduced and sorted as in `interreduce`
+    let mut basis: Vec<Traced<C>> = reducers(&polys, &pairs).into_iter().cloned().collect();
+    for i in 0..basis.len() {
+        let mut others: Vec<Traced<C>> = basis.clone();
+        let f: Traced<C> = others.remove(i);
+        basis[i] = f.reduce(&others.iter().collect::<Vec<_>>()).monic()?;
+    }
+    basis.sort_by(|a, b| order.compare(&leading_monomial(&a.poly), &leading_monomial(&b.poly)));
+    return Some(basis);
+}
+
+// the leading monomials of the reduced basis mod P, then its elements followed
+// by their cofactors, element by element
+fn image<const P: u64>(input: &[Poly<Rational>], order: &Order) -> Option<(Vec<String>, Image<u64>)> {
+    let reduced: Vec<Poly<GF<P>>> = input.iter().map(|p| p.reduce_mod::<P>()).collect::<Option<_>>()?;
+    let basis: Vec<Traced<GF<P>>> = traced_buchberger(&reduced, order)?;
+    let signature: Vec<String> = basis.iter().map(|t| leading_key(&t.poly)).collect();
+    let elements: Image<u64> = basis.iter().map(|t| &t.poly)
+        .chain(basis.iter().flat_map(|t| t.cofactors.iter()))
+        .map(|p| p.monomials.values()
+            .map(|m| (mono_key(m.variables()), (m.variables().clone(), m.coefficient().residue())))
+            .collect())
+        .collect();
+    return Some((signature, elements));
+}
+
+// x with x = a mod m and x = r mod p, in 0..m*p
+fn crt(a: &BigInt, m: &BigInt, r: u64, p: u64) -> BigInt {
+    let p_big: BigInt = BigInt::from(p);
+    let m_inverse: BigInt = (m % &p_big).modpow(&BigInt::from(p - 2), &p_big);
+    let mut t: BigInt = ((BigInt::from(r) - a) % &p_big) * m_inverse % &p_big;
+    if t.is_negative() {
+        t += &p_big;
+    }
+    return a + m * t;
+}
+
+impl Lift {
+    fn new(p: u64, elements: Image<u64>) -> Lift {
+        let elements: Image<BigInt> = elements.into_iter()
+            .map(|e| e.into_iter().map(|(k, (v, r))| (k, (v, BigInt::from(r)))).collect())
+            .collect();
+        return Lift { modulus: BigInt::from(p), elements, candidate: None };
+    }
+
+    fn combine(&mut self, p: u64, elements: Image<u64>) {
+        for (lifted, residues) in self.elements.iter_mut().zip(elements) {
+            // a monomial missing on either side has coefficient zero there
+            for (key, (variables, _)) in residues.iter() {
+                lifted.entry(key.clone()).or_insert((variables.clone(), BigInt::zero()));
+            }
+            for (key, (_, value)) in lifted.iter_mut() {
+                let r: u64 = residues.get(key).map(|(_, r)| *r).unwrap_or(0);
+                *value = crt(value, &self.modulus, r, p);
+            }
+        }
+        self.modulus *= BigInt::from(p);
+    }
+
+    fn reconstruct(&self, order: &Order) -> Option<Vec<Poly<Rational>>> {
+        let mut basis: Vec<Poly<Rational>> = Vec::new();
+        for element in self.elements.iter() {
+            let mut p: Poly<Rational> = Poly { ORDER: order.clone(), ..Poly::default() };
+            let monos: Option<Vec<Mono<Rational>>> = element.values()
+                .map(|(variables, value)| Some(Mono::from((rational_reconstruction(value, &self.modulus)?, variables.clone()))))
+                .collect();
+            p.extend(monos?);
+            basis.push(p);
+        }
+        return Some(basis);
+    }
+}
+
+// the input reduces to zero modulo `basis`, which is a Gröbner basis
+fn is_basis_of(basis: &[Poly<Rational>], input: &[Poly<Rational>]) -> bool {
+    let reduces = |p: Poly<Rational>| -> bool {
+        let (_, mut r) = p / basis.to_vec();
+        return r.zero();
+    };
+    if !input.iter().all(|p| reduces(p.clone())) {
+        return false;
+    }
+    for i in 0..basis.len() {
+        for j in 0..i {
+            if !reduces(Ring::s_poly(basis[i].clone(), basis[j].clone())) {
+                return false;
+            }
+        }
+    }
+    return true;
+}
+
+// every element of `basis` is the combination of `input` its cofactors give,
+// `cofactors` holds one run of `input.len()` polynomials per element
+fn is_combination(basis: &[Poly<Rational>], cofactors: &[Poly<Rational>], input: &[Poly<Rational>]) -> bool {
+    return basis.iter().zip(cofactors.chunks(input.len())).all(|(g, h)| {
+        let mut difference: Poly<Rational> = g.clone();
+        for (h_i, f_i) in h.iter().zip(input.iter()) {
+            difference = difference - h_i.clone() * f_i.clone();
+        }
+        return difference.zero();
+    });
+}
+
+impl Ring<Rational> {
+    // the reduced basis over Q through its images mod the primes in `PRIMES`,
+    // None when those run out before a candidate is verified; never computes
+    // a basis over Q
+    pub fn try_modular_reduced_grobner_basis(set_poly: &HashSet<Poly<Rational>>) -> Option<HashSet<Poly<Rational>>> {
+        let input: Vec<Poly<Rational>> = set_poly.iter().filter(|p| !p.monomials.is_empty()).cloned().collect();
+        let order: Order = match input.first() {
+            Some(p) => p.order(),
+            None => return Some(HashSet::new()),
+        };
+        let input: Vec<Poly<Rational>> = input.into_iter().map(|p| p.with_order(order.clone())).collect();
+        let leading: Vec<BigInt> = input.iter().map(|p| p.clone().leading_coefficient().numerator().clone()).collect();
+        type ImageFn = fn(&[Poly<Rational>], &Order) -> Option<(Vec<String>, Image<u64>)>;
+        let images: [ImageFn; 16] = [
+            image::<{ PRIMES[0] }>, image::<{ PRIMES[1] }>, image::<{ PRIMES[2] }>, image::<{ PRIMES[3] }>,
+            image::<{ PRIMES[4] }>, image::<{ PRIMES[5] }>, image::<{ PRIMES[6] }>, image::<{ PRIMES[7] }>,
+            image::<{ PRIMES[8] }>, image::<{ PRIMES[9] }>, image::<{ PRIMES[10] }>, image::<{ PRIMES[11] }>,
+            image::<{ PRIMES[12] }>, image::<{ PRIMES[13] }>, image::<{ PRIMES[14] }>, image::<{ PRIMES[15] }>,
+        ];
+
+        let mut groups: HashMap<Vec<String>, Lift> = HashMap::new();
+        for (p, image) in PRIMES.iter().zip(images.iter()) {
+            if leading.iter().any(|c| (c % BigInt::from(*p)).is_zero()) {
+                continue;
+            }
+            let (signature, elements) = match image(&input, &order) {
+                Some(result) => result,
+                None => continue,
+            };
+            let n: usize = signature.len();
+            let lift: &mut Lift = match groups.entry(signature) {
+                hash_map::Entry::Occupied(entry) => {
+                    let lift: &mut Lift = entry.into_mut();
+                    lift.combine(*p, elements);
+                    lift
+                }
+                hash_map::Entry::Vacant(entry) => entry.insert(Lift::new(*p, elements)),
+            };
+            let candidate: Option<Vec<Poly<Rational>>> = lift.reconstruct(&order);
+            if let Some(elements) = &candidate && candidate == lift.candidate {
+                let (basis, cofactors) = elements.split_at(n);
+                if is_combination(basis, cofactors, &input) && is_basis_of(basis, &input) {
+                    return Some(basis.iter().cloned().collect());
+                }
+            }
+            lift.candidate = candidate;
+        }
+        return None;
+    }
+
+    // `try_modular_reduced_grobner_basis`, computed over Q directly when the primes run out
+    pub fn modular_reduced_grobner_basis(set_poly: HashSet<Poly<Rational>>) -> HashSet<Poly<Rational>> {
+        return match Ring::try_modular_reduced_grobner_basis(&set_poly) {
+            Some(basis) => basis,
+            None => Ring::reduced_grobner_basis(set_poly),
+        };
+    }
+
+    // `modular_reduced_grobner_basis` under the order of the ring
+    pub fn modular_basis(&self, set_poly: HashSet<Poly<Rational>>) -> HashSet<Poly<Rational>> {
+        return Ring::modular_reduced_grobner_basis(self.configure(set_poly));
+    }
+}
@@ -1,55 +1,77 @@
 use std::collections::*;
+use std::marker::PhantomData;
 use num_notation::Number;
 
 use crate::mono::mono::*;
-use crate::expr::expr::*;
+use crate::mono::order::*;
 use crate::poly::poly::Poly;
+use crate::coefficient::coefficient::Coefficient;
+use crate::ring::buchberger::Selection;
 
-#[derive(Clone)]
-pub struct Ring {
+// a polynomial ring over the coefficient domain `C`, e.g. `Ring<Rational>` for QQ[x, y]
+pub struct Ring<C = Number> {
     pub variables: HashSet<String>,
-    pub polynomials: Option<HashSet<Poly>>
+    pub polynomials: Option<HashSet<Poly<C>>>,
+    pub order: Order,
+    pub(crate) domain: PhantomData<C>,
 }
 
-impl From<&str> for Ring {
+impl<C: Coefficient> Clone for Ring<C> {
+    fn clone(&self) -> Self {
+        Ring {
+            variables: self.variables.clone(),
+            polynomials: self.polynomials.clone(),
+            order: self.order.clone(),
+            domain: PhantomData,
+        }
+    }
+}
+
+impl<C: Coefficient> From<&str> for Ring<C> {
     fn from(_value: &str) -> Self {
         Ring {
             variables: HashSet::from([_value.to_string()]),
-            polynomials: None
+            polynomials: None,
+            order: default_order(),
+            domain: PhantomData,
         }
     }
 }
 
-impl From<String> for Ring {
+impl<C: Coefficient> From<String> for Ring<C> {
     fn from(_value: String) -> Self {
         Ring {
             variables: HashSet::from([_value]),
-            polynomials: None
+            polynomials: None,
+            order: default_order(),
+            domain: PhantomData,
         }
     }
 }
 
-impl From<HashSet<String>> for Ring {
+impl<C: Coefficient> From<HashSet<String>> for Ring<C> {
     fn from(_value: HashSet<String>) -> Self {
         Ring {
             variables: _value,
-            polynomials: None
+            polynomials: None,
+            order: default_order(),
+            domain: PhantomData,
         }
     }
 }
 
-impl From<Ring> for HashSet<String> {
-    fn from(_value: Ring) -> Self {
+impl<C: Coefficient> From<Ring<C>> for HashSet<String> {
+    fn from(_value: Ring<C>) -> Self {
         _value.variables
     }
 }
 
-impl From<HashSet<Poly>> for Ring {
-    fn from(_value: HashSet<Poly>) -> Self {
+impl<C: Coefficient> From<HashSet<Poly<C>>> for Ring<C> {
+    fn from(_value: HashSet<Poly<C>>) -> Self {
         let mut variables: HashSet<String> = HashSet::default();
         for poly in _value.iter() {
             for (_, mono) in poly.monomials().iter() {
-                for (k, v) in mono.variables().iter() {
+                for (k, _v) in mono.variables().iter() {
                     variables.insert(k.clone());
                 }
             }
@@ -57,14 +79,19 @@ impl From<HashSet<Poly>> for Ring {
 
         Ring {
             variables: variables,
-            polynomials: Some(_value)
+            order: match _value.iter().next() {
+                Some(poly) => poly.order(),
+                None => default_order(),
+            },
+            polynomials: Some(_value),
+            domain: PhantomData,
         }
     }
 }
 
-impl Default for Ring {
+impl<C: Coefficient> Default for Ring<C> {
     fn default() -> Self {
-        Ring { variables: HashSet::default(), polynomials: None }
+        Ring { variables: HashSet::default(), polynomials: None, order: default_order(), domain: PhantomData }
     }
 }
 
@@ -78,94 +105,75 @@ pub trait GrobnerBasis {
     fn reduced_set(&self, set_poly :HashSet<Poly>);
 }
 
-impl Ring {
-    pub fn configure(&self, set_poly: HashSet<Poly>) -> HashSet<Poly> {
-        let tmp_self: Ring = self.clone();
+impl<C: Coefficient> Ring<C> {
+    // name of the coefficient domain, see `Coefficient::domain`
+    pub fn domain(&self) -> String {
+        return C::domain();
+    }
+
+    pub fn with_order(mut self, order: Order) -> Ring<C> {
+        self.order = order;
+        return self;
+    }
+
+    // reduced Gröbner basis of `set_poly` under the order of the ring
+    pub fn basis(&self, set_poly: HashSet<Poly<C>>) -> HashSet<Poly<C>> {
+        return Ring::reduced_grobner_basis(self.configure(set_poly));
+    }
+
+    // `set_poly` over all variables of the ring and under its order
+    pub fn configure(&self, set_poly: HashSet<Poly<C>>) -> HashSet<Poly<C>> {
+        let tmp_self: Ring<C> = self.clone();
         let mut tmp_hash_map: BTreeMap<String, Number> = BTreeMap::new();
         
         for key in tmp_self.variables.into_iter(){
             tmp_hash_map.insert(key , Number::Decimal(0.0));
         }
 
-        let tmp_exp: Expr = Expr::from((Number::Decimal(1.0), tmp_hash_map));
-        let tmp_mono: Mono = Mono::from((tmp_exp, None));
+        let tmp_mono: Mono<C> = Mono::from((C::one(), tmp_hash_map));
 
-        let tmp_set_poly: HashSet<Poly> = set_poly;
-
-        for mut poly in tmp_set_poly.iter(){
-            poly = &(poly.clone() * tmp_mono.clone());
-        }
+        let tmp_set_poly: HashSet<Poly<C>> = set_poly.into_iter().map(|poly| (poly * tmp_mono.clone()).with_order(self.order.clone())).collect();
 
         tmp_set_poly
-
-        // for mut poly in tmp_set_poly.into_iter() {
-        //     for mut mono: Mono in poly.mono
-        // }
     }
 
-    fn s_poly(mut a: Poly, mut b: Poly) -> Poly {
-        let a_lt_mono: &Mono = a.leading_term();
-        let b_lt_mono: &Mono = b.leading_term();
+    pub fn s_poly(mut a: Poly<C>, mut b: Poly<C>) -> Poly<C> {
+        let a_lt_mono: &Mono<C> = a.leading_term();
+        let b_lt_mono: &Mono<C> = b.leading_term();
 
-        let L: Mono = Mono::lcm(&(a_lt_mono.clone()), &(b_lt_mono.clone()));
+        let L: Mono<C> = Mono::lcm(&(a_lt_mono.clone()), &(b_lt_mono.clone()));
 
-        let m: Mono = (L.clone() / a_lt_mono.clone()).unwrap();
-        let m_prime: Mono = (L / b_lt_mono.clone()).unwrap();
+        let m: Mono<C> = (L.clone() / a_lt_mono.clone()).unwrap();
+        let m_prime: Mono<C> = (L / b_lt_mono.clone()).unwrap();
 
         let s0 = a.clone() * m;
-        let s1: Poly = b.clone() * m_prime;
+        let s1: Poly<C> = b.clone() * m_prime;
 
         return s0 - s1
     }
 
-    pub fn reduced_grobner_basis(set_poly :HashSet<Poly>) -> HashSet<Poly> {
-        let mut tmp_set_poly: HashSet<Poly> = set_poly.clone();
-
-        let mut i: usize = 0;
-        let mut j: usize = 0;
-
-        while i < tmp_set_poly.len() {
-            while j < tmp_set_poly.len() {
-                let vec_set_poly: Vec<Poly> = tmp_set_poly.iter().cloned().collect();
-                if i != j {
-                    let f: &Poly = &vec_set_poly[i];
-                    let g: &Poly = &vec_set_poly[j];
-                    let s: Poly = Ring::s_poly(f.clone(), g.clone());
-                    let (_, mut r): (Poly, Poly) = s.clone() / vec_set_poly;
-
-                    if !r.zero() {
-                        tmp_set_poly.insert(s);
-                        let tmp_set_poly_2 = Ring::reduced_set(tmp_set_poly.clone());
-
-                        if tmp_set_poly != tmp_set_poly_2 {
-                            tmp_set_poly = tmp_set_poly_2;
-                            i -= 1;
-                            j = 0;
-                            break;
-                        }
-                    }
-                }
-                j += 1;
-            }
-            i += 1;
-        }
-        tmp_set_poly
+    // see `Ring::buchberger`
+    pub fn reduced_grobner_basis(set_poly :HashSet<Poly<C>>) -> HashSet<Poly<C>> {
+        let (basis, _) = Ring::buchberger(set_poly, Selection::Normal);
+        basis.into_iter().collect()
     }
 
-    pub fn reduced_set(set_poly :HashSet<Poly>) -> HashSet<Poly> {
+    pub fn reduced_set(set_poly :HashSet<Poly<C>>) -> HashSet<Poly<C>> {
         let mut i: usize = 0;
-        let mut vec_set_poly: Vec<Poly> = set_poly.into_iter().collect(); 
+        let mut vec_set_poly: Vec<Poly<C>> = set_poly.into_iter().collect(); 
 
         while i < vec_set_poly.len() {
-            let mut temp_vec_set_poly: Vec<Poly> = vec_set_poly.clone();
+            let mut temp_vec_set_poly: Vec<Poly<C>> = vec_set_poly.clone();
             let f_ = temp_vec_set_poly.remove(i);
-            let (q, mut r) = f_ / temp_vec_set_poly;
+            let (_q, mut r) = f_ / temp_vec_set_poly;
             if r.zero() {
                 vec_set_poly.remove(i);
             }
-            i += 1;
+            else {
+                i += 1;
+            }
         }
-        let mut result: HashSet<Poly> = HashSet::new();
+        let mut result: HashSet<Poly<C>> = HashSet::new();
         for p in vec_set_poly.into_iter() {
             result.insert(p);
         }